@@ -0,0 +1,161 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Types shared by the example contracts, so that contracts calling each other agree on how
+//! token ids, amounts and errors are encoded.
+
+use scale::{Encode, Decode};
+
+/// Token ids are 128 bits wide so that bridged ids and the split fungible/non-fungible
+/// encoding fit. They used to be `u32`, so callers encoding ids as 4 bytes must switch to 16.
+pub type TokenId = u128;
+pub type TokenBalance = u128;
+
+/// Each variant's SCALE index is pinned with `#[codec(index)]`, so the error code seen over
+/// the ABI never changes between releases. New variants take the next unused index; existing
+/// indices are never reordered or reused.
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature="std", derive(scale_info::TypeInfo))]
+pub enum Error {
+    /// `required` of token `id` was needed but only `available` was held.
+    #[codec(index = 0)]
+    InsufficientBalance { id: TokenId, available: TokenBalance, required: TokenBalance },
+    #[codec(index = 1)]
+    ApprovalForSelf,
+    /// The lengths of the two parallel arrays that did not match.
+    #[codec(index = 2)]
+    InvalidArrayLength(u32, u32),
+    #[codec(index = 3)]
+    CannotFetchValue,
+    #[codec(index = 4)]
+    OnlyCreator,
+    #[codec(index = 5)]
+    NotApproved,
+    #[codec(index = 6)]
+    OnlyOwner,
+    #[codec(index = 7)]
+    AccountFrozen,
+    #[codec(index = 8)]
+    TransfersLockedUntilReveal,
+    #[codec(index = 9)]
+    MaxSupplyExceeded,
+    #[codec(index = 10)]
+    SupplyLocked,
+    #[codec(index = 11)]
+    PermitExpired,
+    #[codec(index = 12)]
+    NonceAlreadyUsed,
+    #[codec(index = 13)]
+    InvalidNonce,
+    #[codec(index = 14)]
+    InvalidSignature,
+    #[codec(index = 15)]
+    WalletMintLimitExceeded,
+    #[codec(index = 16)]
+    TokensLocked,
+    #[codec(index = 17)]
+    NotApprovedMarketplace,
+    #[codec(index = 18)]
+    InvalidFee,
+    #[codec(index = 19)]
+    InsufficientFee,
+    #[codec(index = 20)]
+    NativeTransferFailed,
+    #[codec(index = 21)]
+    ZeroValue,
+    #[codec(index = 22)]
+    AllowanceOverflow,
+    #[codec(index = 23)]
+    NoWrapper,
+    #[codec(index = 24)]
+    WrapperCallFailed,
+    #[codec(index = 25)]
+    InvalidSnapshot,
+    #[codec(index = 26)]
+    NotNftType,
+    #[codec(index = 27)]
+    InvalidNftMint,
+    #[codec(index = 28)]
+    InvalidRange,
+    #[codec(index = 29)]
+    TransferToSelfContract,
+    #[codec(index = 30)]
+    OperatorNotAllowed,
+    #[codec(index = 31)]
+    ClaimCapExceeded,
+    #[codec(index = 32)]
+    UriAlreadySet,
+    #[codec(index = 33)]
+    VoucherAlreadyRedeemed,
+    #[codec(index = 34)]
+    InvalidVoucherSignature,
+    #[codec(index = 35)]
+    InsufficientPayment,
+    #[codec(index = 36)]
+    BatchTooLarge,
+    #[codec(index = 37)]
+    TransferCooldown,
+    #[codec(index = 38)]
+    NotPendingOwner,
+    #[codec(index = 39)]
+    TokenPaused,
+    #[codec(index = 40)]
+    TooManyOperators,
+    #[codec(index = 41)]
+    ApprovalsPaused,
+    #[codec(index = 42)]
+    MintingFinalized,
+    #[codec(index = 43)]
+    EmptyBatch,
+    #[codec(index = 44)]
+    RoyaltyNotEnforced,
+    #[codec(index = 45)]
+    SupplyUnderflow,
+    /// The id is reserved for tokens only the contract itself may create.
+    #[codec(index = 46)]
+    ReservedId,
+    #[codec(index = 47)]
+    SupplyOverflow,
+    #[codec(index = 48)]
+    FeeOverflow,
+    /// The id was never created or reserved, so `create` could still hand it out.
+    #[codec(index = 49)]
+    TokenNotFound,
+    #[codec(index = 50)]
+    InsufficientAllowance,
+    #[codec(index = 51)]
+    TokenExists,
+    #[codec(index = 52)]
+    NotOwner,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_types_scale_round_trip() {
+        let id: TokenId = u128::MAX - 1;
+        let encoded = id.encode();
+        assert_eq!(encoded.len(), 16);
+        assert_eq!(<TokenId as Decode>::decode(&mut &encoded[..]), Ok(id));
+
+        let balance: TokenBalance = 1_000_000;
+        let encoded = balance.encode();
+        assert_eq!(encoded.len(), 16);
+        assert_eq!(<TokenBalance as Decode>::decode(&mut &encoded[..]), Ok(balance));
+    }
+
+    #[test]
+    fn error_scale_round_trips() {
+        let errors = [
+            Error::InsufficientBalance { id: 7, available: 1, required: 2 },
+            Error::InvalidArrayLength(2, 3),
+            Error::OnlyOwner,
+            Error::SupplyUnderflow,
+        ];
+        for error in errors.iter() {
+            let encoded = error.encode();
+            assert_eq!(<Error as Decode>::decode(&mut &encoded[..]).as_ref(), Ok(error));
+        }
+    }
+}
@@ -0,0 +1,309 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+#[ink::contract]
+pub mod erc20 {
+    use ink_storage::collections::{
+        HashMap as StorageHashMap,
+    };
+
+    pub use common::{Error, TokenBalance, TokenId};
+
+    /// An ERC20 holds a single token; errors that name a token id report this one.
+    pub const TOKEN_ID: TokenId = 0;
+
+    #[ink(storage)]
+    pub struct Erc20 {
+        owner: AccountId,
+        total_supply: TokenBalance,
+        balances: StorageHashMap<AccountId, TokenBalance>,
+        allowances: StorageHashMap<(AccountId, AccountId), TokenBalance>,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        value: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: TokenBalance,
+    }
+
+    impl Erc20 {
+        /// Creates a new Erc20 contract with `initial_supply` assigned to the caller.
+        #[ink(constructor)]
+        pub fn new(initial_supply: TokenBalance) -> Self {
+            let caller = Self::env().caller();
+            let mut balances = StorageHashMap::new();
+            balances.insert(caller, initial_supply);
+
+            Self::env().emit_event(Transfer {
+                from: AccountId::from([0x0; 32]),
+                to: caller,
+                value: initial_supply,
+            });
+
+            Self {
+                owner: caller,
+                total_supply: initial_supply,
+                balances,
+                allowances: StorageHashMap::new(),
+            }
+        }
+
+        /// Returns the owner of the contract.
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Returns the total token supply.
+        #[ink(message)]
+        pub fn total_supply(&self) -> TokenBalance {
+            self.total_supply
+        }
+
+        /// Returns the balance of `account`.
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> TokenBalance {
+            self.balance_of_or_zero(&account)
+        }
+
+        /// Returns the amount `spender` is still allowed to withdraw from `owner`.
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> TokenBalance {
+            self.allowance_of_or_zero(&owner, &spender)
+        }
+
+        /// Transfers `value` tokens from the caller to `to`.
+        #[ink(message)]
+        pub fn transfer(&mut self, to: AccountId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.transfer_from_to(&caller, &to, value)?;
+
+            Ok(())
+        }
+
+        /// Allows `spender` to withdraw from the caller's account up to `value` tokens.
+        /// Emits an {Approval} event.
+        #[ink(message)]
+        pub fn approve(&mut self, spender: AccountId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.allowances.insert((caller, spender), value);
+
+            self.env().emit_event(Approval {
+                owner: caller,
+                spender,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Transfers `value` tokens from `from` to `to` on the caller's allowance.
+        #[ink(message)]
+        pub fn transfer_from(&mut self, from: AccountId, to: AccountId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            let allowance = self.allowance_of_or_zero(&from, &caller);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance);
+            }
+
+            self.transfer_from_to(&from, &to, value)?;
+            self.allowances.insert((from, caller), allowance - value);
+
+            Ok(())
+        }
+
+        /// Creates `value` tokens and assigns them to `to`.
+        /// The selector is pinned so the subgame1 wrapper can call it.
+        #[ink(message, selector = "0xCFDD9AA2")]
+        pub fn mint(&mut self, to: AccountId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::OnlyOwner);
+            }
+
+            if to == AccountId::from([0x0; 32]) {
+                return Err(Error::NotApproved);
+            }
+
+            let to_balance = self.balance_of_or_zero(&to);
+            self.balances.insert(to, to_balance + value);
+            self.total_supply += value;
+
+            self.env().emit_event(Transfer {
+                from: AccountId::from([0x0; 32]),
+                to,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Destroys `value` tokens from `from`.
+        /// The selector is pinned so the subgame1 wrapper can call it.
+        #[ink(message, selector = "0xB1EFC17B")]
+        pub fn burn(&mut self, from: AccountId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::OnlyOwner);
+            }
+
+            let from_balance = self.balance_of_or_zero(&from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance { id: TOKEN_ID, available: from_balance, required: value });
+            }
+
+            self.balances.insert(from, from_balance - value);
+            self.total_supply -= value;
+
+            self.env().emit_event(Transfer {
+                from,
+                to: AccountId::from([0x0; 32]),
+                value,
+            });
+
+            Ok(())
+        }
+
+        fn transfer_from_to(&mut self, from: &AccountId, to: &AccountId, value: TokenBalance) -> Result<(), Error> {
+            if *to == AccountId::from([0x0; 32]) {
+                return Err(Error::NotApproved);
+            }
+
+            let from_balance = self.balance_of_or_zero(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance { id: TOKEN_ID, available: from_balance, required: value });
+            }
+
+            self.balances.insert(*from, from_balance - value);
+            let to_balance = self.balance_of_or_zero(to);
+            self.balances.insert(*to, to_balance + value);
+
+            self.env().emit_event(Transfer {
+                from: *from,
+                to: *to,
+                value,
+            });
+
+            Ok(())
+        }
+
+        fn balance_of_or_zero(&self, account: &AccountId) -> TokenBalance {
+            *self.balances.get(account).unwrap_or(&0)
+        }
+
+        fn allowance_of_or_zero(&self, owner: &AccountId, spender: &AccountId) -> TokenBalance {
+            *self.allowances.get(&(*owner, *spender)).unwrap_or(&0)
+        }
+    }
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+
+        fn set_sender(sender: AccountId) {
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            test::push_execution_context::<ink_env::DefaultEnvironment>(
+                sender,
+                callee,
+                1000000,
+                1000000,
+                test::CallData::new(call::Selector::new([0x00; 4])), // dummy
+            );
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let erc20 = Erc20::new(1000);
+            assert_eq!(erc20.total_supply(), 1000);
+            assert_eq!(erc20.balance_of(accounts.alice), 1000);
+            assert_eq!(erc20.get_owner(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer(accounts.bob, 200), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 800);
+            assert_eq!(erc20.balance_of(accounts.bob), 200);
+        }
+
+        #[ink::test]
+        fn transfer_fails_on_insufficient_balance() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer(accounts.bob, 1001), Err(Error::InsufficientBalance { id: TOKEN_ID, available: 1000, required: 1001 }));
+            assert_eq!(erc20.balance_of(accounts.alice), 1000);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn transfer_from_spends_allowance() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.approve(accounts.bob, 300), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 300);
+
+            set_sender(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 200), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 100);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 200), Err(Error::InsufficientAllowance));
+            assert_eq!(erc20.balance_of(accounts.alice), 800);
+            assert_eq!(erc20.balance_of(accounts.charlie), 200);
+        }
+
+        #[ink::test]
+        fn mint_and_burn_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.mint(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+            assert_eq!(erc20.total_supply(), 1500);
+            assert_eq!(erc20.burn(accounts.bob, 600), Err(Error::InsufficientBalance { id: TOKEN_ID, available: 500, required: 600 }));
+            assert_eq!(erc20.burn(accounts.bob, 200), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 300);
+            assert_eq!(erc20.total_supply(), 1300);
+
+            set_sender(accounts.bob);
+            assert_eq!(erc20.mint(accounts.bob, 500), Err(Error::OnlyOwner));
+            assert_eq!(erc20.burn(accounts.alice, 500), Err(Error::OnlyOwner));
+        }
+    }
+}
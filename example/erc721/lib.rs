@@ -0,0 +1,387 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+use ink_prelude::{
+    vec::Vec,
+};
+
+#[ink::contract]
+pub mod erc721 {
+    use ink_storage::collections::{
+        HashMap as StorageHashMap,
+    };
+    use crate::Vec;
+
+    pub use common::{Error, TokenId};
+
+    #[ink(storage)]
+    pub struct Erc721 {
+        owner: AccountId,
+        token_owner: StorageHashMap<TokenId, AccountId>,
+        token_approvals: StorageHashMap<TokenId, AccountId>,
+        owned_tokens_count: StorageHashMap<AccountId, u32>,
+        operator_approvals: StorageHashMap<(AccountId, AccountId), bool>,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    #[ink(event)]
+    pub struct ApprovalForAll {
+        #[ink(topic)]
+        account: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    impl Erc721 {
+        /// Creates a new Erc721 contract.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                owner: Self::env().caller(),
+                token_owner: StorageHashMap::new(),
+                token_approvals: StorageHashMap::new(),
+                owned_tokens_count: StorageHashMap::new(),
+                operator_approvals: StorageHashMap::new(),
+            }
+        }
+
+        /// Constructors can delegate to other constructors.
+        #[ink(constructor)]
+        pub fn default() -> Self {
+            Self::new()
+        }
+
+        /// Returns the owner of the contract.
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Returns the number of tokens owned by `account`.
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId) -> u32 {
+            *self.owned_tokens_count.get(&account).unwrap_or(&0)
+        }
+
+        /// Returns the owner of token `id`.
+        #[ink(message)]
+        pub fn owner_of(&self, id: TokenId) -> Option<AccountId> {
+            self.token_owner.get(&id).cloned()
+        }
+
+        /// Returns the account approved to transfer token `id`.
+        #[ink(message)]
+        pub fn get_approved(&self, id: TokenId) -> Option<AccountId> {
+            self.token_approvals.get(&id).cloned()
+        }
+
+        /// Returns true if `operator` is approved to transfer ``account``'s tokens.
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, account: AccountId, operator: AccountId) -> bool {
+            self.approved_for_all(&account, &operator)
+        }
+
+        /// Approves `to` to transfer token `id` on behalf of the caller.
+        /// Emits an {Approval} event.
+        #[ink(message)]
+        pub fn approve(&mut self, to: AccountId, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+
+            if !(owner == caller || self.approved_for_all(&owner, &caller)) {
+                return Err(Error::NotApproved);
+            }
+
+            if to == owner {
+                return Err(Error::ApprovalForSelf);
+            }
+
+            self.token_approvals.insert(id, to);
+
+            self.env().emit_event(Approval {
+                from: owner,
+                to,
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Grants or revokes permission to `operator` to transfer the caller's tokens, according to `approved`.
+        /// Emits an {ApprovalForAll} event.
+        #[ink(message)]
+        pub fn set_approval_for_all(&mut self, operator: AccountId, approved: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if operator == caller {
+                return Err(Error::ApprovalForSelf);
+            }
+
+            self.operator_approvals.insert((caller, operator), approved);
+
+            self.env().emit_event(ApprovalForAll {
+                account: caller,
+                operator,
+                approved,
+            });
+
+            Ok(())
+        }
+
+        /// Transfers token `id` from `from` to `to`.
+        #[ink(message)]
+        pub fn transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if !self.approved_or_owner(caller, id) {
+                return Err(Error::NotApproved);
+            }
+
+            self.transfer_token_from(&from, &to, id)?;
+
+            Ok(())
+        }
+
+        /// Transfers token `id` from `from` to `to`, passing `data` along with the transfer.
+        #[ink(message)]
+        pub fn safe_transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId, _data: Vec<u8>) -> Result<(), Error> {
+            self.transfer_from(from, to, id)
+        }
+
+        /// Creates token `id` and assigns it to `to`.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::OnlyOwner);
+            }
+
+            if to == AccountId::from([0x0; 32]) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.token_owner.contains_key(&id) {
+                return Err(Error::TokenExists);
+            }
+
+            self.token_owner.insert(id, to);
+            self.increase_count_of(&to);
+
+            self.env().emit_event(Transfer {
+                from: AccountId::from([0x0; 32]),
+                to,
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Destroys token `id`. Only the token owner can burn it.
+        #[ink(message)]
+        pub fn burn(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+
+            if owner != caller {
+                return Err(Error::NotOwner);
+            }
+
+            self.token_owner.take(&id);
+            self.token_approvals.take(&id);
+            self.decrease_count_of(&owner);
+
+            self.env().emit_event(Transfer {
+                from: owner,
+                to: AccountId::from([0x0; 32]),
+                id,
+            });
+
+            Ok(())
+        }
+
+        fn transfer_token_from(&mut self, from: &AccountId, to: &AccountId, id: TokenId) -> Result<(), Error> {
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+
+            if owner != *from {
+                return Err(Error::NotOwner);
+            }
+
+            if *to == AccountId::from([0x0; 32]) {
+                return Err(Error::NotApproved);
+            }
+
+            self.token_approvals.take(&id);
+            self.decrease_count_of(from);
+            self.increase_count_of(to);
+            self.token_owner.insert(id, *to);
+
+            self.env().emit_event(Transfer {
+                from: *from,
+                to: *to,
+                id,
+            });
+
+            Ok(())
+        }
+
+        fn increase_count_of(&mut self, account: &AccountId) {
+            let count = self.balance_of(*account);
+            self.owned_tokens_count.insert(*account, count + 1);
+        }
+
+        fn decrease_count_of(&mut self, account: &AccountId) {
+            let count = self.balance_of(*account);
+            self.owned_tokens_count.insert(*account, count - 1);
+        }
+
+        fn approved_or_owner(&self, caller: AccountId, id: TokenId) -> bool {
+            match self.owner_of(id) {
+                Some(owner) => {
+                    owner == caller
+                        || self.get_approved(id) == Some(caller)
+                        || self.approved_for_all(&owner, &caller)
+                }
+                None => false,
+            }
+        }
+
+        fn approved_for_all(&self, account: &AccountId, operator: &AccountId) -> bool {
+            *self.operator_approvals.get(&(*account, *operator)).unwrap_or(&false)
+        }
+    }
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+
+        fn set_sender(sender: AccountId) {
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            test::push_execution_context::<ink_env::DefaultEnvironment>(
+                sender,
+                callee,
+                1000000,
+                1000000,
+                test::CallData::new(call::Selector::new([0x00; 4])), // dummy
+            );
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc721 = Erc721::new();
+            assert_eq!(erc721.owner_of(1), None);
+            assert_eq!(erc721.mint(accounts.alice, 1), Ok(()));
+            assert_eq!(erc721.owner_of(1), Some(accounts.alice));
+            assert_eq!(erc721.balance_of(accounts.alice), 1);
+        }
+
+        #[ink::test]
+        fn double_mint_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc721 = Erc721::new();
+            assert_eq!(erc721.mint(accounts.alice, 1), Ok(()));
+            assert_eq!(erc721.mint(accounts.bob, 1), Err(Error::TokenExists));
+            assert_eq!(erc721.owner_of(1), Some(accounts.alice));
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc721 = Erc721::new();
+            assert_eq!(erc721.mint(accounts.alice, 1), Ok(()));
+            assert_eq!(erc721.transfer_from(accounts.alice, accounts.bob, 1), Ok(()));
+            assert_eq!(erc721.owner_of(1), Some(accounts.bob));
+            assert_eq!(erc721.balance_of(accounts.alice), 0);
+            assert_eq!(erc721.balance_of(accounts.bob), 1);
+            assert_eq!(erc721.transfer_from(accounts.alice, accounts.bob, 1), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn approve_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc721 = Erc721::new();
+            assert_eq!(erc721.mint(accounts.alice, 1), Ok(()));
+            assert_eq!(erc721.approve(accounts.bob, 1), Ok(()));
+            assert_eq!(erc721.get_approved(1), Some(accounts.bob));
+
+            set_sender(accounts.bob);
+            assert_eq!(erc721.safe_transfer_from(accounts.alice, accounts.charlie, 1, Vec::new()), Ok(()));
+            assert_eq!(erc721.owner_of(1), Some(accounts.charlie));
+            assert_eq!(erc721.get_approved(1), None);
+        }
+
+        #[ink::test]
+        fn set_approval_for_all_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc721 = Erc721::new();
+            assert_eq!(erc721.mint(accounts.alice, 1), Ok(()));
+            assert_eq!(erc721.set_approval_for_all(accounts.bob, true), Ok(()));
+            assert_eq!(erc721.is_approved_for_all(accounts.alice, accounts.bob), true);
+
+            set_sender(accounts.bob);
+            assert_eq!(erc721.transfer_from(accounts.alice, accounts.charlie, 1), Ok(()));
+            assert_eq!(erc721.owner_of(1), Some(accounts.charlie));
+
+            set_sender(accounts.eve);
+            assert_eq!(erc721.transfer_from(accounts.charlie, accounts.eve, 1), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn burn_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut erc721 = Erc721::new();
+            assert_eq!(erc721.mint(accounts.alice, 1), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(erc721.burn(1), Err(Error::NotOwner));
+            assert_eq!(erc721.mint(accounts.bob, 2), Err(Error::OnlyOwner));
+
+            set_sender(accounts.alice);
+            assert_eq!(erc721.burn(1), Ok(()));
+            assert_eq!(erc721.owner_of(1), None);
+            assert_eq!(erc721.balance_of(accounts.alice), 0);
+        }
+    }
+}
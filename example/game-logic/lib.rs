@@ -0,0 +1,215 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_lang as ink;
+
+/// A minimal game logic contract that rewards players by minting items on a deployed subgame1
+/// token, as a worked example of the cross-contract pattern.
+///
+/// To wire it up, deploy subgame1, deploy this contract with the token's account id, then call
+/// `set_authorized_minter(<this contract>, true)` on the token as its owner. From then on every
+/// `reward_player` call mints through the token's `MultiToken::mint`.
+#[ink::contract]
+pub mod game_logic {
+    use ink_storage::Lazy;
+    use ink_env::call::FromAccountId;
+    use scale::{Encode, Decode};
+    use common::{TokenId, TokenBalance};
+    use subgame1::multi_token::MultiToken;
+    use subgame1::subgame1::Subgame1;
+
+    pub type TokenError = common::Error;
+
+    #[ink(storage)]
+    pub struct GameLogic {
+        owner: AccountId,
+        token: Lazy<Subgame1>,
+    }
+
+    #[ink(event)]
+    pub struct PlayerRewarded {
+        #[ink(topic)]
+        player: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+        amount: TokenBalance,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(feature="std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        OnlyOwner,
+        /// The token rejected the call, e.g. because this contract is not an authorized minter.
+        Token(TokenError),
+    }
+
+    impl GameLogic {
+        /// Creates a game logic contract that mints on the subgame1 token deployed at `token`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                owner: Self::env().caller(),
+                token: Lazy::new(Subgame1::from_account_id(token)),
+            }
+        }
+
+        /// Returns the owner of the contract.
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Mints `amount` of token `id` to `player`.
+        #[ink(message)]
+        pub fn reward_player(&mut self, player: AccountId, id: TokenId, amount: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::OnlyOwner);
+            }
+
+            MultiToken::mint(&mut *self.token, player, id, amount).map_err(Error::Token)?;
+
+            self.env().emit_event(PlayerRewarded {
+                player,
+                id,
+                amount,
+            });
+
+            Ok(())
+        }
+    }
+
+    /// The off-chain environment cannot execute calls into another contract, so these tests
+    /// stop short of the mint. The mint itself is covered by subgame1's
+    /// `authorized_game_contract_can_mint` test, and the whole flow by `e2e_tests` below.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+
+        fn set_sender(sender: AccountId) {
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            test::push_execution_context::<ink_env::DefaultEnvironment>(
+                sender,
+                callee,
+                1000000,
+                1000000,
+                test::CallData::new(call::Selector::new([0x00; 4])), // dummy
+            );
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let game_logic = GameLogic::new(accounts.django);
+            assert_eq!(game_logic.get_owner(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn only_owner_can_reward() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut game_logic = GameLogic::new(accounts.django);
+
+            set_sender(accounts.bob);
+            assert_eq!(game_logic.reward_player(accounts.bob, 1, 10), Err(Error::OnlyOwner));
+        }
+    }
+
+    /// Deploys subgame1 and this contract to a running contracts node and rewards a player
+    /// through the real cross-contract call, which the off-chain environment cannot execute.
+    ///
+    /// Only built with the `e2e` feature. The test drives `cargo contract` (with its extrinsics
+    /// support), so it needs the `cargo-contract` CLI installed, a contracts node listening at
+    /// `CONTRACTS_NODE_URL` (default `ws://127.0.0.1:9944`) with the dev accounts funded, and
+    /// both contracts already built with `cargo contract build`:
+    ///
+    /// ```sh
+    /// cargo test --features e2e -- --test-threads 1
+    /// ```
+    #[cfg(all(test, feature = "e2e"))]
+    mod e2e_tests {
+        use std::process::Command;
+        use serde_json::Value;
+
+        /// SS58 address of the `//Bob` dev account, the rewarded player.
+        const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+
+        fn node_url() -> String {
+            std::env::var("CONTRACTS_NODE_URL").unwrap_or_else(|_| "ws://127.0.0.1:9944".into())
+        }
+
+        /// Runs `cargo contract <args>` in the crate at `manifest_dir` as `//Alice` and returns
+        /// its JSON output.
+        fn cargo_contract(manifest_dir: &str, args: &[&str]) -> Value {
+            let output = Command::new("cargo")
+                .current_dir(manifest_dir)
+                .arg("contract")
+                .args(args)
+                .args(&["--suri", "//Alice", "--url", &node_url(), "--output-json"])
+                .output()
+                .expect("Cannot run cargo contract");
+            assert!(output.status.success(), "cargo contract {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+            serde_json::from_slice(&output.stdout).expect("Cannot parse cargo contract output")
+        }
+
+        fn instantiate(manifest_dir: &str, args: &[&str]) -> String {
+            let mut command = vec!["instantiate", "--constructor", "new", "--execute", "--skip-confirm"];
+            if !args.is_empty() {
+                command.push("--args");
+                command.extend_from_slice(args);
+            }
+            let output = cargo_contract(manifest_dir, &command);
+            output["contract"].as_str().expect("No contract address in output").to_string()
+        }
+
+        fn execute(manifest_dir: &str, contract: &str, message: &str, args: &[&str]) {
+            let mut command = vec!["call", "--contract", contract, "--message", message, "--execute", "--skip-confirm", "--args"];
+            command.extend_from_slice(args);
+            cargo_contract(manifest_dir, &command);
+        }
+
+        /// Returns the first unsigned integer in the decoded return value of a dry-run call.
+        fn read_uint(manifest_dir: &str, contract: &str, message: &str, args: &[&str]) -> u128 {
+            fn find_uint(value: &Value) -> Option<u128> {
+                match value {
+                    Value::Object(map) => match map.get("UInt") {
+                        Some(uint) => uint.as_u64().map(u128::from).or_else(|| uint.as_str()?.parse().ok()),
+                        None => map.values().find_map(find_uint),
+                    },
+                    Value::Array(values) => values.iter().find_map(find_uint),
+                    _ => None,
+                }
+            }
+
+            let mut command = vec!["call", "--contract", contract, "--message", message, "--args"];
+            command.extend_from_slice(args);
+            let output = cargo_contract(manifest_dir, &command);
+            find_uint(&output["data"]).expect("No integer in call result")
+        }
+
+        #[test]
+        fn reward_player_mints_on_deployed_token() {
+            let token_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../subgame1");
+            let game_dir = env!("CARGO_MANIFEST_DIR");
+
+            let token = instantiate(token_dir, &[]);
+            execute(token_dir, &token, "create", &["0x01"]);
+            let game = instantiate(game_dir, &[&token]);
+            execute(token_dir, &token, "set_authorized_minter", &[&game, "true"]);
+
+            let before = read_uint(token_dir, &token, "balance_of", &[BOB, "1"]);
+            execute(game_dir, &game, "reward_player", &[BOB, "1", "10"]);
+            let after = read_uint(token_dir, &token, "balance_of", &[BOB, "1"]);
+
+            assert_eq!(after, before + 10);
+        }
+    }
+}
@@ -6,28 +6,196 @@ use ink_prelude::{
     vec::Vec,
 };
 
+pub mod multi_token;
+pub mod psp37;
+pub mod selectors;
+
 #[ink::contract]
 pub mod subgame1 {
     use ink_storage::collections::{
         HashMap as StorageHashMap,
     };
+    use ink_env::hash::Blake2x256;
     use scale::{Encode, Decode};
     use crate::Vec;
+    use crate::multi_token::MultiToken;
+    use crate::psp37::{PSP37, PSP37Error};
+
+    pub use common::{Error, TokenBalance, TokenId};
+
+    /// Set in the ids of non-fungible token types and of their instances.
+    pub const NF_BIT: TokenId = 1 << 127;
+    /// Selects the type half of a split id. The lower 64 bits hold the instance index.
+    pub const TYPE_MASK: TokenId = (u64::MAX as TokenId) << 64;
+    /// Token type backed 1:1 by the chain's native token through `deposit` and `withdraw`.
+    /// It is the highest fungible id, so `create` never hands it out.
+    pub const WRAPPED_NATIVE_ID: TokenId = NF_BIT - 1;
+
+    /// Selector of `Erc20::mint`, pinned in the erc20 example.
+    const WRAPPER_MINT_SELECTOR: [u8; 4] = [0xcf, 0xdd, 0x9a, 0xa2];
+    /// Selector of `Erc20::burn`, pinned in the erc20 example.
+    const WRAPPER_BURN_SELECTOR: [u8; 4] = [0xb1, 0xef, 0xc1, 0x7b];
+
+    /// Raw bytes of the zero account, which stands for "nobody": it is the `from` of mints,
+    /// the `to` of burns, and is never a valid holder.
+    const ZERO_ACCOUNT: [u8; 32] = [0x0; 32];
+
+    /// Upper bound on the number of entries a batch message accepts, keeping its execution cost bounded.
+    pub const MAX_BATCH_SIZE: usize = 100;
+
+    /// Upper bound on the operators one account can have approved at a time, and on the default
+    /// operators, keeping `revoke_all_operators` within a bounded cost.
+    pub const MAX_OPERATORS_PER_ACCOUNT: usize = 50;
+
+    /// Domain tag leading every `permit` payload, so a signature made for another kind of
+    /// message can never be replayed as an approval.
+    const PERMIT_DOMAIN: &[u8] = b"subgame1:permit";
+    /// Domain tag leading every `mint_voucher` payload.
+    const VOUCHER_DOMAIN: &[u8] = b"subgame1:voucher";
+
+    /// Prefixes passed to `key_of`, one per map, so equal tuples never share a key across maps.
+    const BALANCES_PREFIX: &[u8] = b"balances";
+    const OPERATOR_APPROVALS_PREFIX: &[u8] = b"operator_approvals";
+
+    /// Delivers `wrap` and `unwrap`'s call into the wrapper ERC20. Everything else those messages
+    /// do is the same in every build; only the delivery is swapped for a mock in tests, as the
+    /// off-chain environment cannot call another contract.
+    trait WrapperCaller {
+        fn call(token: &Subgame1, wrapper: AccountId, selector: [u8; 4], account: AccountId, value: TokenBalance) -> Result<(), Error>;
+    }
+
+    /// Calls the deployed wrapper contract.
+    enum ContractWrapperCaller {}
+
+    impl WrapperCaller for ContractWrapperCaller {
+        fn call(_token: &Subgame1, wrapper: AccountId, selector: [u8; 4], account: AccountId, value: TokenBalance) -> Result<(), Error> {
+            use ink_env::call::{build_call, utils::ReturnType, ExecutionInput, Selector};
+
+            build_call::<ink_env::DefaultEnvironment>()
+                .callee(wrapper)
+                .gas_limit(0)
+                .transferred_value(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selector))
+                        .push_arg(account)
+                        .push_arg(value)
+                )
+                .returns::<ReturnType<Result<(), Error>>>()
+                .fire()
+                .map_err(|_| Error::WrapperCallFailed)?
+                .map_err(|_| Error::WrapperCallFailed)
+        }
+    }
 
-    pub type TokenId = u32;
-    pub type TokenBalance = u128;
+    #[cfg(not(test))]
+    type WrapperTransport = ContractWrapperCaller;
+    #[cfg(test)]
+    type WrapperTransport = tests::mock_erc20::MockWrapperCaller;
+
+    /// Checks `signature` is ``signer``'s ed25519 signature over `message_hash`. Ed25519 accounts
+    /// are their public key, so no key recovery is needed.
+    fn verify_signature(signer: &AccountId, signature: &[u8; 64], message_hash: &[u8; 32]) -> bool {
+        match ed25519_compact::PublicKey::from_slice(&signer.encode()) {
+            Ok(public_key) => public_key.verify(message_hash, &ed25519_compact::Signature::new(*signature)).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    fn is_zero_account(account: &AccountId) -> bool {
+        *account == AccountId::from(ZERO_ACCOUNT)
+    }
+
+    /// Every lock currently holding back part of the contract, as returned by `lock_state`.
+    #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct LockState {
+        /// Token ids paused by `pause_id`.
+        pub paused_ids: Vec<TokenId>,
+        /// Number of accounts frozen by `freeze`.
+        pub frozen_accounts: u32,
+        /// Whether new operator approvals are paused.
+        pub approvals_paused: bool,
+        /// Whether minting has been permanently finalized.
+        pub minting_finalized: bool,
+        /// Whether transfers are held until the reveal block, which has not been reached yet.
+        pub transfers_locked_until_reveal: bool,
+        /// Number of (account, id) pairs with tokens staked through `lock`.
+        pub locked_positions: u32,
+    }
 
     /// Defines the storage of your contract.
     /// Add new fields to the below struct in order
     /// to add new static storage fields to your contract.
     #[ink(storage)]
     pub struct Subgame1 {
-        balances: StorageHashMap<(AccountId, TokenId), TokenBalance>,
-        operator_approvals: StorageHashMap<(AccountId, AccountId), bool>,
+        owner: AccountId,
+        pending_owner: Option<AccountId>,
+        /// Keyed by `key_of(BALANCES_PREFIX, &(account, id))`.
+        balances: StorageHashMap<Hash, TokenBalance>,
+        /// Keyed by `key_of(OPERATOR_APPROVALS_PREFIX, &(account, operator))`.
+        operator_approvals: StorageHashMap<Hash, (bool, Option<Timestamp>)>,
+        approved_operators: StorageHashMap<AccountId, Vec<AccountId>>,
+        operator_allowlist: StorageHashMap<AccountId, bool>,
+        enforce_allowlist: bool,
+        enforce_royalties: bool,
+        approvals_paused: bool,
+        minting_finalized: bool,
+        nonces: StorageHashMap<AccountId, u128>,
+        redeemed_vouchers: StorageHashMap<u128, bool>,
+        default_operators: StorageHashMap<AccountId, bool>,
+        revoked_default_operators: StorageHashMap<(AccountId, AccountId), bool>,
 
         next_token_id: TokenId,
+        next_nft_type: u64,
+        nft_max_index: StorageHashMap<TokenId, u64>,
         token_creator: StorageHashMap<TokenId, AccountId>,
         token_uri: StorageHashMap<TokenId, Vec<u8>>,
+        token_uris: StorageHashMap<TokenId, Vec<u8>>,
+        contract_uri: Option<Vec<u8>>,
+        decimals: StorageHashMap<TokenId, u8>,
+        base_uri: Vec<u8>,
+        fallback_uri: Vec<u8>,
+
+        total_supply: StorageHashMap<TokenId, TokenBalance>,
+        burned_totals: StorageHashMap<TokenId, TokenBalance>,
+        last_active: StorageHashMap<AccountId, Timestamp>,
+        holder_index: StorageHashMap<TokenId, Vec<AccountId>>,
+        token_count: u32,
+        token_type_count: u32,
+        holder_count: u32,
+        owned_tokens: StorageHashMap<AccountId, Vec<TokenId>>,
+        allowances: StorageHashMap<(AccountId, AccountId, TokenId), TokenBalance>,
+
+        max_supply: StorageHashMap<TokenId, TokenBalance>,
+        transfer_cooldown: StorageHashMap<TokenId, Timestamp>,
+        last_received: StorageHashMap<(AccountId, TokenId), Timestamp>,
+        supply_locked: StorageHashMap<TokenId, bool>,
+        auto_lock_at_cap: bool,
+        max_mint_per_wallet: StorageHashMap<TokenId, TokenBalance>,
+        minted_by_wallet: StorageHashMap<(AccountId, TokenId), TokenBalance>,
+        claim_caps: StorageHashMap<(AccountId, TokenId), TokenBalance>,
+        locks: StorageHashMap<(AccountId, TokenId), (TokenBalance, Timestamp)>,
+        locked_balances: StorageHashMap<(AccountId, TokenId), TokenBalance>,
+        companion_id: Option<TokenId>,
+
+        marketplaces: StorageHashMap<AccountId, u16>,
+        treasury: AccountId,
+        transfer_fee_bps: u16,
+        fee_recipient: Option<AccountId>,
+        mint_fee: Balance,
+
+        frozen: StorageHashMap<AccountId, bool>,
+        paused_ids: StorageHashMap<TokenId, bool>,
+        authorized_minter_contracts: StorageHashMap<AccountId, bool>,
+
+        reveal_block: BlockNumber,
+        transfers_before_reveal: bool,
+
+        wrapper: Option<(AccountId, TokenId)>,
+        accept_contract_transfers: bool,
+
+        snapshot_id: u32,
+        balance_checkpoints: StorageHashMap<(AccountId, TokenId), Vec<(u32, TokenBalance)>>,
     }
 
     #[ink(event)]
@@ -52,6 +220,37 @@ pub mod subgame1 {
         values: Vec<TokenBalance>,
     }
 
+    /// Follows each `TransferSingle` of a call that emits several of them, giving its position
+    /// `seq` (from 0) among the `batch_len` transfers of that call.
+    #[ink(event)]
+    pub struct BatchSequence {
+        #[ink(topic)]
+        operator: AccountId,
+        seq: u32,
+        batch_len: u32,
+    }
+
+    /// Emitted when `account` burns `ids`/`values` as the inputs of crafting recipe `recipe_id`.
+    #[ink(event)]
+    pub struct Redeemed {
+        #[ink(topic)]
+        account: AccountId,
+        ids: Vec<TokenId>,
+        values: Vec<TokenBalance>,
+        #[ink(topic)]
+        recipe_id: u32,
+    }
+
+    /// Emitted when ownership of the contract changes hands. `new_owner` is the zero account
+    /// once ownership has been renounced.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
     #[ink(event)]
     pub struct ApprovalForAll {
         #[ink(topic)]
@@ -62,11 +261,29 @@ pub mod subgame1 {
     }
 
     #[ink(event)]
-    pub struct URI {
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        id: Option<TokenId>,
+        value: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Uri {
         value: Vec<u8>,
+        #[ink(topic)]
         id: TokenId,
     }
 
+    /// Signals that the metadata of every id in `from_id..=to_id` changed, as in EIP-4906.
+    #[ink(event)]
+    pub struct BatchMetadataUpdate {
+        from_id: TokenId,
+        to_id: TokenId,
+    }
+
     #[ink(event)]
     pub struct TokenCreated {
         #[ink(topic)]
@@ -75,15 +292,76 @@ pub mod subgame1 {
         uri: Vec<u8>,
     }
 
-    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
-    #[cfg_attr(feature="std", derive(scale_info::TypeInfo))]
-    pub enum Error {
-        InsufficientBalance,
-        ApprovalForSelf,
-        InvalidArrayLength,
-        CannotFetchValue,
-        OnlyCreator,
-        NotApproved,
+    #[ink(event)]
+    pub struct MarketplaceSale {
+        #[ink(topic)]
+        marketplace: AccountId,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        id: TokenId,
+        value: TokenBalance,
+        sale_price: Balance,
+        fee: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Locked {
+        #[ink(topic)]
+        account: AccountId,
+        id: TokenId,
+        value: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct Unlocked {
+        #[ink(topic)]
+        account: AccountId,
+        id: TokenId,
+        value: TokenBalance,
+    }
+
+    #[ink(event)]
+    pub struct AccountFrozen {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AccountUnfrozen {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct IdPaused {
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    #[ink(event)]
+    pub struct IdUnpaused {
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    #[ink(event)]
+    pub struct MintingFinalized {
+        #[ink(topic)]
+        operator: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct NoncesInvalidated {
+        #[ink(topic)]
+        account: AccountId,
+        nonce: u128,
+    }
+
+    #[ink(event)]
+    pub struct VoucherRevoked {
+        nonce: u128,
     }
 
     impl Subgame1 {
@@ -91,12 +369,107 @@ pub mod subgame1 {
         #[ink(constructor)]
         pub fn new() -> Self {
             Self {
+                owner: Self::env().caller(),
+                pending_owner: None,
                 balances: StorageHashMap::new(),
                 operator_approvals: StorageHashMap::new(),
+                approved_operators: StorageHashMap::new(),
+                operator_allowlist: StorageHashMap::new(),
+                enforce_allowlist: false,
+                enforce_royalties: false,
+                approvals_paused: false,
+                minting_finalized: false,
+                nonces: StorageHashMap::new(),
+                redeemed_vouchers: StorageHashMap::new(),
+                default_operators: StorageHashMap::new(),
+                revoked_default_operators: StorageHashMap::new(),
                 next_token_id: TokenId::from(1u32),
+                next_nft_type: 1,
+                nft_max_index: StorageHashMap::new(),
                 token_creator: StorageHashMap::new(),
                 token_uri: StorageHashMap::new(),
+                token_uris: StorageHashMap::new(),
+                contract_uri: None,
+                decimals: StorageHashMap::new(),
+                base_uri: Vec::new(),
+                fallback_uri: Vec::new(),
+                total_supply: StorageHashMap::new(),
+                burned_totals: StorageHashMap::new(),
+                last_active: StorageHashMap::new(),
+                holder_index: StorageHashMap::new(),
+                token_count: 0,
+                token_type_count: 0,
+                holder_count: 0,
+                owned_tokens: StorageHashMap::new(),
+                allowances: StorageHashMap::new(),
+                max_supply: StorageHashMap::new(),
+                transfer_cooldown: StorageHashMap::new(),
+                last_received: StorageHashMap::new(),
+                supply_locked: StorageHashMap::new(),
+                auto_lock_at_cap: false,
+                max_mint_per_wallet: StorageHashMap::new(),
+                minted_by_wallet: StorageHashMap::new(),
+                claim_caps: StorageHashMap::new(),
+                locks: StorageHashMap::new(),
+                locked_balances: StorageHashMap::new(),
+                companion_id: None,
+                marketplaces: StorageHashMap::new(),
+                treasury: Self::env().caller(),
+                transfer_fee_bps: 0,
+                fee_recipient: None,
+                mint_fee: 0,
+                frozen: StorageHashMap::new(),
+                paused_ids: StorageHashMap::new(),
+                authorized_minter_contracts: StorageHashMap::new(),
+                reveal_block: 0,
+                transfers_before_reveal: true,
+                wrapper: None,
+                accept_contract_transfers: false,
+                snapshot_id: 0,
+                balance_checkpoints: StorageHashMap::new(),
+            }
+        }
+
+        /// Creates a new Subgame1 contract and mints `values` of token types `ids` to the caller.
+        /// Each id is registered with the caller as its creator.
+        ///
+        /// Panics if `ids` and `values` differ in length or any value is zero.
+        #[ink(constructor)]
+        pub fn new_with_supply(ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Self {
+            assert_eq!(ids.len(), values.len(), "ids and values must have the same length");
+            assert!(values.iter().all(|value| *value > 0), "values must not be zero");
+
+            let caller = Self::env().caller();
+            let mut contract = Self::new();
+
+            for (id, value) in ids.iter().zip(values.iter()) {
+                if !contract.token_creator.contains_key(id) {
+                    contract.reserve_token(*id, caller, Vec::new())
+                        .expect("initial ids must not be reserved");
+                }
+
+                contract.mint_token_to(&caller, id, *value)
+                    .expect("initial supply must be mintable");
             }
+
+            Self::env().emit_event(TransferBatch {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to: caller,
+                ids,
+                values,
+            });
+
+            contract
+        }
+
+        /// Creates a new Subgame1 contract whose token type `id` can be wrapped into the ERC20
+        /// contract at `wrapper`. This contract must be the owner of `wrapper`.
+        #[ink(constructor)]
+        pub fn with_wrapper(wrapper: AccountId, id: TokenId) -> Self {
+            let mut contract = Self::new();
+            contract.wrapper = Some((wrapper, id));
+            contract
         }
 
         /// Constructors can delegate to other constructors.
@@ -105,444 +478,7037 @@ pub mod subgame1 {
             Self::new()
         }
 
+        /// Returns the owner of the contract. Same as `owner`, kept for existing callers.
         #[ink(message)]
-        pub fn create(&mut self, uri: Vec<u8>) -> Result<(), Error> {
-            let caller = self.env().caller();
-            let id = self.next_token_id;
-
-            self.token_creator.insert(id, caller);
-            self.token_uri.insert(id, uri.clone());
-            self.next_token_id = id + 1;
-
-            self.env().emit_event(TokenCreated {
-                creator: caller,
-                id,
-                uri,
-            });
-
-            Ok(())
+        pub fn get_owner(&self) -> AccountId {
+            self.owner()
         }
 
-        /// Returns the creator of the token.
+        /// Returns the owner of the contract, the zero account once ownership has been renounced.
         #[ink(message)]
-        pub fn creator_of(&self, id: TokenId) -> Option<AccountId> {
-            self.token_creator.get(&id).cloned()
+        pub fn owner(&self) -> AccountId {
+            self.owner
         }
 
-        /// Returns the uri of the token.
+        /// Returns the account nominated by `transfer_ownership` that has not accepted yet, if any.
         #[ink(message)]
-        pub fn uri_of(&self, id: TokenId) -> Option<Vec<u8>> {
-            self.token_uri.get(&id).cloned()
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
         }
 
+        /// Nominates `new_owner` as the next owner. Ownership only moves once `new_owner` calls
+        /// `accept_ownership`, so a mistyped account cannot lock the contract.
         #[ink(message)]
-        pub fn set_uri(&mut self, id: TokenId, uri: Vec<u8>) -> Result<(), Error> {
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
             let caller = self.env().caller();
-            if !self.is_creator(caller, id) {
-                return Err(Error::OnlyCreator);
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
             }
 
-            self.token_uri.insert(id, uri.clone());
+            if is_zero_account(&new_owner) {
+                return Err(Error::NotApproved);
+            }
 
-            self.env().emit_event(URI {
-                value: uri,
-                id,
-            });
+            self.pending_owner = Some(new_owner);
 
             Ok(())
         }
 
-        /// Get the balance of an account's Tokens
+        /// Makes the caller the owner if it was nominated by `transfer_ownership`.
+        /// Emits an {OwnershipTransferred} event.
         #[ink(message)]
-        pub fn balance_of(&self, account: AccountId, id: TokenId) -> TokenBalance {
-            self.balance_of_or_zero(&account, &id)
+        pub fn accept_ownership(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::NotPendingOwner);
+            }
+
+            self.set_owner(caller);
+
+            Ok(())
         }
 
-        /// Get the balance of multiple account/token pairs
+        /// Gives up ownership for good, leaving the contract without an owner. Every owner-only
+        /// message fails from then on.
+        /// Emits an {OwnershipTransferred} event.
         #[ink(message)]
-        pub fn balance_of_batch(&self, accounts: Vec<AccountId>, ids: Vec<TokenId>) -> Result<Vec<TokenBalance>, Error> {
-            if accounts.len() != ids.len() {
-                return Err(Error::InvalidArrayLength);
+        pub fn renounce_ownership(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
             }
 
-            let mut batch_balances: Vec<TokenBalance> = Vec::new();
+            self.set_owner(AccountId::from(ZERO_ACCOUNT));
 
-            for i in 0..accounts.len() {
-                batch_balances.push(self.balance_of_or_zero(&accounts[i], &ids[i]));
+            Ok(())
+        }
+
+        /// Freezes `account`, blocking any transfer from or to it and any mint into it.
+        #[ink(message)]
+        pub fn freeze(&mut self, account: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
             }
 
-            Ok(batch_balances)
+            self.frozen.insert(account, true);
+
+            self.env().emit_event(AccountFrozen {
+                account,
+            });
+
+            Ok(())
         }
 
-        /// Grants or revokes permission to `operator` to transfer the caller's tokens, according to `approved`.
-        /// Emits an {ApprovalForAll} event.
+        /// Lifts a previous freeze on `account`.
         #[ink(message)]
-        pub fn set_approval_for_all(&mut self, operator: AccountId, approved: bool) -> Result<(), Error> {
+        pub fn unfreeze(&mut self, account: AccountId) -> Result<(), Error> {
             let caller = self.env().caller();
-
-            if operator == caller {
-                return Err(Error::ApprovalForSelf);
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
             }
 
-            if self.approved_for_all(&caller, &operator) {
-                let status = self
-                    .operator_approvals
-                    .get_mut(&(caller, operator))
-                    .ok_or(Error::CannotFetchValue)?;
-                *status = approved;
-            } else {
-                self.operator_approvals.insert((caller, operator), approved);
-            }
+            self.frozen.take(&account);
 
-            self.env().emit_event(ApprovalForAll {
-                account: caller,
-                operator,
-                approved,
+            self.env().emit_event(AccountUnfrozen {
+                account,
             });
 
             Ok(())
         }
 
-        /// Returns true if `operator` is approved to transfer ``account``'s tokens.
+        /// Returns true if `account` is frozen.
         #[ink(message)]
-        pub fn is_approved_for_all(&self, account: AccountId, operator: AccountId) -> bool {
-            self.approved_for_all(&account, &operator)
+        pub fn is_frozen(&self, account: AccountId) -> bool {
+            self.frozen_or_false(&account)
         }
 
-        /// Transfers `value` tokens of token type `id` from `from` to `to`.
+        /// Returns the block timestamp at which `account` last sent, received, minted or burned
+        /// tokens, if it ever did.
         #[ink(message)]
-        pub fn safe_transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
-            let caller = self.env().caller();
+        pub fn last_active_of(&self, account: AccountId) -> Option<Timestamp> {
+            self.last_active.get(&account).cloned()
+        }
 
-            if !self.approved_or_owner(from, caller) {
-                return Err(Error::NotApproved);
+        /// Pauses token `id`, blocking every transfer and mint of it while other ids keep moving.
+        #[ink(message)]
+        pub fn pause_id(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
             }
 
-            self.transfer_token_from(&from, &to, &id, value)?;
+            self.paused_ids.insert(id, true);
 
-            self.env().emit_event(TransferSingle {
-                operator: caller,
-                from,
-                to,
+            self.env().emit_event(IdPaused {
                 id,
-                value,
             });
 
             Ok(())
         }
 
-        /// Send multiple types of Tokens from `from` to `to`.
+        /// Lifts a previous pause on token `id`.
         #[ink(message)]
-        pub fn safe_batch_transfer_from(&mut self, from: AccountId, to: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
+        pub fn unpause_id(&mut self, id: TokenId) -> Result<(), Error> {
             let caller = self.env().caller();
-
-            if ids.len() != values.len() {
-                return Err(Error::InvalidArrayLength);
-            }
-
-            if !self.approved_or_owner(from, caller) {
-                return Err(Error::NotApproved);
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
             }
 
-            for i in 0..ids.len() {
-                let id = ids[i];
-                let value = values[i];
-
-                self.transfer_token_from(&from, &to, &id, value)?;
-            }
+            self.paused_ids.take(&id);
 
-            self.env().emit_event(TransferBatch {
-                operator: caller,
-                from,
-                to,
-                ids,
-                values,
+            self.env().emit_event(IdUnpaused {
+                id,
             });
 
             Ok(())
         }
 
-        /// Creates `value` tokens of token type `id`, and assigns them to `account`.
+        /// Returns true if token `id` is paused.
         #[ink(message)]
-        pub fn mint(&mut self, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
-            let caller = self.env().caller();
+        pub fn is_id_paused(&self, id: TokenId) -> bool {
+            self.paused_or_false(&id)
+        }
 
-            if !self.is_creator(caller, id) {
-                return Err(Error::OnlyCreator);
+        /// Allows or disallows a game logic contract to mint any token type.
+        #[ink(message)]
+        pub fn set_authorized_minter(&mut self, contract: AccountId, authorized: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
             }
 
-            if to == AccountId::from([0x0; 32]) {
-                return Err(Error::NotApproved);
+            if authorized {
+                self.authorized_minter_contracts.insert(contract, true);
+            } else {
+                self.authorized_minter_contracts.take(&contract);
             }
 
-            self.add_token_to(&to, &id, value)?;
-
-            self.env().emit_event(TransferSingle {
-                operator: caller,
-                from: AccountId::from([0x0; 32]),
-                to,
-                id,
-                value,
-            });
-
             Ok(())
         }
 
+        /// Returns true if `contract` is allowed to mint any token type.
         #[ink(message)]
-        pub fn mint_batch(&mut self, to: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
-            let caller = self.env().caller();
+        pub fn is_authorized_minter(&self, contract: AccountId) -> bool {
+            *self.authorized_minter_contracts.get(&contract).unwrap_or(&false)
+        }
 
-            if to == AccountId::from([0x0; 32]) {
-                return Err(Error::NotApproved);
+        /// Sets the block at which the collection metadata is revealed.
+        #[ink(message)]
+        pub fn set_reveal_block(&mut self, reveal_block: BlockNumber) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
             }
 
-            if ids.len() != values.len() {
-                return Err(Error::InvalidArrayLength);
+            self.reveal_block = reveal_block;
+
+            Ok(())
+        }
+
+        /// Returns the block at which the collection metadata is revealed.
+        #[ink(message)]
+        pub fn reveal_block(&self) -> BlockNumber {
+            self.reveal_block
+        }
+
+        /// Allows or forbids transfers before the reveal block. Minting is always allowed.
+        #[ink(message)]
+        pub fn set_transfers_before_reveal(&mut self, allowed: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.transfers_before_reveal = allowed;
+
+            Ok(())
+        }
+
+        /// Returns true if tokens can be transferred before the reveal block.
+        #[ink(message)]
+        pub fn transfers_before_reveal(&self) -> bool {
+            self.transfers_before_reveal
+        }
+
+        #[ink(message)]
+        pub fn create(&mut self, uri: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if self.minting_finalized {
+                return Err(Error::MintingFinalized);
+            }
+
+            let id = self.allocate_token(caller, uri.clone());
+
+            self.env().emit_event(Uri {
+                value: uri,
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Creates a non-fungible token type and returns its id. Instances of the type are
+        /// minted one at a time with `mint_nft`.
+        #[ink(message)]
+        pub fn create_nft_type(&mut self, uri: Vec<u8>) -> Result<TokenId, Error> {
+            let caller = self.env().caller();
+
+            if self.minting_finalized {
+                return Err(Error::MintingFinalized);
+            }
+
+            let type_id = NF_BIT | (TokenId::from(self.next_nft_type) << 64);
+            self.next_nft_type += 1;
+            self.register_token(type_id, caller, uri);
+
+            Ok(type_id)
+        }
+
+        /// Mints the next instance of the non-fungible type `type_id` to `to` and returns its id.
+        #[ink(message)]
+        pub fn mint_nft(&mut self, type_id: TokenId, to: AccountId) -> Result<TokenId, Error> {
+            let caller = self.env().caller();
+
+            if !self.is_nft_type(type_id) {
+                return Err(Error::NotNftType);
+            }
+
+            if !self.is_minter(caller, type_id) {
+                return Err(Error::OnlyCreator);
+            }
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            let index = *self.nft_max_index.get(&type_id).unwrap_or(&0) + 1;
+            let id = type_id | TokenId::from(index);
+            self.nft_max_index.insert(type_id, index);
+            self.mint_token_to(&to, &id, 1)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to,
+                id,
+                value: 1,
+            });
+
+            Ok(id)
+        }
+
+        /// Returns true if `id` is an instance of a non-fungible token type.
+        #[ink(message)]
+        pub fn is_nft(&self, id: TokenId) -> bool {
+            id & NF_BIT != 0 && self.index(id) != 0
+        }
+
+        /// Returns the type half of `id`. For fungible tokens this is 0.
+        #[ink(message)]
+        pub fn base_type(&self, id: TokenId) -> TokenId {
+            id & TYPE_MASK
+        }
+
+        /// Returns the instance index of a non-fungible `id`.
+        #[ink(message)]
+        pub fn index(&self, id: TokenId) -> u64 {
+            (id & !TYPE_MASK) as u64
+        }
+
+        /// Sets how much of token `id` `account` may still claim with `claim`.
+        #[ink(message)]
+        pub fn set_claim_cap(&mut self, account: AccountId, id: TokenId, cap: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.claim_caps.insert((account, id), cap);
+
+            Ok(())
+        }
+
+        /// Returns how much of token `id` `account` may still claim.
+        #[ink(message)]
+        pub fn claim_cap_of(&self, account: AccountId, id: TokenId) -> TokenBalance {
+            *self.claim_caps.get(&(account, id)).unwrap_or(&0)
+        }
+
+        /// Mints `value` of token `id` to the caller out of its remaining claim cap.
+        /// Charges the mint fee like `mint`.
+        #[ink(message, payable)]
+        pub fn claim(&mut self, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            let cap = self.claim_cap_of(caller, id);
+            if value > cap {
+                return Err(Error::ClaimCapExceeded);
+            }
+
+            if self.frozen_or_false(&caller) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if !self.is_registered(id) {
+                return Err(Error::TokenNotFound);
+            }
+
+            self.ensure_mintable(&caller, &id, value)?;
+            self.settle_mint_fee(caller, value)?;
+
+            self.mint_token_to(&caller, &id, value)?;
+            self.claim_caps.insert((caller, id), cap - value);
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to: caller,
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Mints `value` of token `id` to `to` and sets its uri in the same call, so there is no
+        /// window in which the token exists without metadata. Fails if `id` already has a uri.
+        /// Owner-only, so no mint fee is charged.
+        #[ink(message)]
+        pub fn mint_with_uri(&mut self, to: AccountId, id: TokenId, value: TokenBalance, uri: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            if self.token_uri.get(&id).map_or(false, |uri| !uri.is_empty()) {
+                return Err(Error::UriAlreadySet);
+            }
+
+            // Reserving an id at or above the wrapped native id would move `next_token_id`
+            // into the reserved and non-fungible ranges.
+            if id >= WRAPPED_NATIVE_ID {
+                return Err(Error::ReservedId);
+            }
+
+            self.mint_token_to(&to, &id, value)?;
+            if self.token_creator.contains_key(&id) {
+                self.token_uri.insert(id, uri.clone());
+            } else {
+                self.reserve_token(id, caller, uri.clone())?;
+            }
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to,
+                id,
+                value,
+            });
+
+            self.env().emit_event(Uri {
+                value: uri,
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Mints each of `values` of `ids` to `to` and sets the matching entry of `uris` as its uri,
+        /// for launching a set of distinct tokens in one call. Fails without minting anything if
+        /// any id already has a uri or is listed twice. Owner-only, so no mint fee is charged.
+        /// Emits a {TransferBatch} event and a {Uri} event per id.
+        #[ink(message)]
+        pub fn mint_batch_with_uris(&mut self, to: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>, uris: Vec<Vec<u8>>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if ids.len() != values.len() {
+                return Err(Error::InvalidArrayLength(ids.len() as u32, values.len() as u32));
+            }
+
+            if ids.len() != uris.len() {
+                return Err(Error::InvalidArrayLength(ids.len() as u32, uris.len() as u32));
+            }
+
+            if ids.is_empty() {
+                return Err(Error::EmptyBatch);
+            }
+
+            if ids.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge);
+            }
+
+            if values.iter().any(|value| *value == 0) {
+                return Err(Error::ZeroValue);
+            }
+
+            for (i, id) in ids.iter().enumerate() {
+                if ids[..i].contains(id) || self.token_uri.get(id).map_or(false, |uri| !uri.is_empty()) {
+                    return Err(Error::UriAlreadySet);
+                }
+
+                if *id >= WRAPPED_NATIVE_ID {
+                    return Err(Error::ReservedId);
+                }
             }
 
             for i in 0..ids.len() {
                 let id = ids[i];
-                let value = values[i];
-                
-                if !self.is_creator(caller, id) {
-                    return Err(Error::OnlyCreator);
+
+                self.mint_token_to(&to, &id, values[i])?;
+                if self.token_creator.contains_key(&id) {
+                    self.token_uri.insert(id, uris[i].clone());
+                } else {
+                    self.reserve_token(id, caller, uris[i].clone())?;
                 }
+            }
+
+            self.env().emit_event(TransferBatch {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to,
+                ids: ids.clone(),
+                values,
+            });
+
+            for (id, uri) in ids.into_iter().zip(uris.into_iter()) {
+                self.env().emit_event(Uri {
+                    value: uri,
+                    id,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Reserves a fresh token id and mints `initial_supply` of it to `to`.
+        /// Owner-only, so no mint fee is charged. Returns the new id.
+        #[ink(message)]
+        pub fn create_token(&mut self, initial_supply: TokenBalance, to: AccountId) -> Result<TokenId, Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if self.minting_finalized {
+                return Err(Error::MintingFinalized);
+            }
+
+            let id = self.allocate_token(caller, Vec::new());
+
+            if initial_supply > 0 {
+                self.mint_token_to(&to, &id, initial_supply)?;
+
+                self.env().emit_event(TransferSingle {
+                    operator: caller,
+                    from: AccountId::from(ZERO_ACCOUNT),
+                    to,
+                    id,
+                    value: initial_supply,
+                });
+            }
+
+            Ok(id)
+        }
+
+        /// Returns the creator of the token.
+        #[ink(message)]
+        pub fn creator_of(&self, id: TokenId) -> Option<AccountId> {
+            self.token_creator.get(&id).cloned()
+        }
+
+        /// Returns the uri of the token.
+        #[ink(message)]
+        pub fn uri_of(&self, id: TokenId) -> Option<Vec<u8>> {
+            self.token_uri.get(&id).cloned()
+        }
+
+        #[ink(message)]
+        pub fn set_uri(&mut self, id: TokenId, uri: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_creator(caller, id) {
+                return Err(Error::OnlyCreator);
+            }
+
+            self.token_uri.insert(id, uri.clone());
+
+            self.env().emit_event(Uri {
+                value: uri,
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Caps the total supply of token `id` at `cap`.
+        #[ink(message)]
+        pub fn set_max_supply(&mut self, id: TokenId, cap: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_creator(caller, id) {
+                return Err(Error::OnlyCreator);
+            }
+
+            if self.supply_locked_or_false(&id) {
+                return Err(Error::SupplyLocked);
+            }
+
+            if cap < self.supply_of_or_zero(&id) {
+                return Err(Error::MaxSupplyExceeded);
+            }
+
+            self.max_supply.insert(id, cap);
+
+            Ok(())
+        }
+
+        /// Returns up to `limit` holders of token `id` with their balances, locked tokens included,
+        /// skipping the first `start`. Holders are listed in the order they first received `id`;
+        /// an account that drops to zero leaves the list.
+        #[ink(message)]
+        pub fn holders_of(&self, id: TokenId, start: u32, limit: u32) -> Vec<(AccountId, TokenBalance)> {
+            self.holder_index
+                .get(&id)
+                .map(|holders| {
+                    holders
+                        .iter()
+                        .skip(start as usize)
+                        .take(limit as usize)
+                        .map(|holder| (*holder, self.balance_of_or_zero(holder, &id).saturating_add(self.locked_balance_of(*holder, id))))
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        /// Returns the cumulative amount of token `id` ever burned. Transfers never count.
+        #[ink(message)]
+        pub fn total_burned(&self, id: TokenId) -> TokenBalance {
+            *self.burned_totals.get(&id).unwrap_or(&0)
+        }
+
+        /// Returns the supply cap of token `id`, if any.
+        #[ink(message)]
+        pub fn max_supply_of(&self, id: TokenId) -> Option<TokenBalance> {
+            self.max_supply.get(&id).cloned()
+        }
+
+        /// Returns how much more of token `id` can be minted under its cap, or `None` if it has no cap.
+        #[ink(message)]
+        pub fn remaining_supply(&self, id: TokenId) -> Option<TokenBalance> {
+            self.max_supply_of(id).map(|cap| cap.saturating_sub(self.supply_of_or_zero(&id)))
+        }
+
+        /// Requires holders of token `id` to wait `cooldown` after receiving it before they can
+        /// transfer it on. Only receipts after the cooldown is set are tracked; zero disables it.
+        #[ink(message)]
+        pub fn set_transfer_cooldown(&mut self, id: TokenId, cooldown: Timestamp) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_creator(caller, id) {
+                return Err(Error::OnlyCreator);
+            }
+
+            if cooldown == 0 {
+                self.transfer_cooldown.take(&id);
+            } else {
+                self.transfer_cooldown.insert(id, cooldown);
+            }
+
+            Ok(())
+        }
+
+        /// Returns the transfer cooldown of token `id`, or zero if there is none.
+        #[ink(message)]
+        pub fn transfer_cooldown_of(&self, id: TokenId) -> Timestamp {
+            *self.transfer_cooldown.get(&id).unwrap_or(&0)
+        }
+
+        /// Permanently prevents any further minting of token `id`.
+        #[ink(message)]
+        pub fn lock_supply(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_creator(caller, id) {
+                return Err(Error::OnlyCreator);
+            }
+
+            self.supply_locked.insert(id, true);
+
+            Ok(())
+        }
+
+        /// Permanently stops all minting and token creation, including claims and vouchers.
+        /// Deposits of native token stay open, since they are backed 1:1 and can be withdrawn, and
+        /// so does `unwrap`, which only brings back tokens that were wrapped. Unlike pausing, this cannot be undone.
+        /// Emits a {MintingFinalized} event.
+        #[ink(message)]
+        pub fn finalize_minting(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.minting_finalized = true;
+
+            self.env().emit_event(MintingFinalized {
+                operator: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Returns true if minting has been permanently finalized.
+        #[ink(message)]
+        pub fn minting_finalized(&self) -> bool {
+            self.minting_finalized
+        }
+
+        /// Returns every lock currently in force, for an operational status check. The cost
+        /// grows with the number of paused ids.
+        #[ink(message)]
+        pub fn lock_state(&self) -> LockState {
+            // `unpause_id`, `unfreeze` and `unlock` remove their entries, so every remaining
+            // one is an active lock.
+            LockState {
+                paused_ids: self.paused_ids.keys().copied().collect(),
+                frozen_accounts: self.frozen.len(),
+                approvals_paused: self.approvals_paused,
+                minting_finalized: self.minting_finalized,
+                transfers_locked_until_reveal: !self.transfers_before_reveal && self.env().block_number() < self.reveal_block,
+                locked_positions: self.locked_balances.len(),
+            }
+        }
+
+        /// Returns true if the supply of token `id` is locked.
+        #[ink(message)]
+        pub fn is_supply_locked(&self, id: TokenId) -> bool {
+            self.supply_locked_or_false(&id)
+        }
+
+        /// When enabled, the supply of a token is locked as soon as it reaches its cap,
+        /// so it cannot be minted again even after burns.
+        #[ink(message)]
+        pub fn set_auto_lock_at_cap(&mut self, enabled: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.auto_lock_at_cap = enabled;
+
+            Ok(())
+        }
+
+        /// Returns true if supplies are locked automatically once they reach their cap.
+        #[ink(message)]
+        pub fn auto_lock_at_cap(&self) -> bool {
+            self.auto_lock_at_cap
+        }
+
+        /// Returns the metadata uri of token `id`, in order of precedence:
+        /// the owner's override set with `set_token_uri`; its own uri when one was set; for an id that was ever minted, the base uri with every
+        /// `{id}` replaced by the zero-padded lowercase hex id; otherwise the fallback uri, which
+        /// is empty unless the owner set one.
+        #[ink(message)]
+        pub fn uri(&self, id: TokenId) -> Vec<u8> {
+            if let Some(uri) = self.token_uris.get(&id) {
+                return uri.clone();
+            }
+
+            match self.token_uri.get(&id) {
+                Some(uri) if !uri.is_empty() => uri.clone(),
+                _ if self.total_supply.contains_key(&id) => self.expand_base_uri(id),
+                _ => self.fallback_uri.clone(),
+            }
+        }
+
+        /// Overrides the uri of token `id` with `uri`, whoever created it, for collections whose items
+        /// each have their own metadata. Emits a {Uri} event.
+        #[ink(message)]
+        pub fn set_token_uri(&mut self, id: TokenId, uri: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.token_uris.insert(id, uri.clone());
+
+            self.env().emit_event(Uri {
+                value: uri,
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the uri reported for ids that were never minted and have no uri of their own.
+        #[ink(message)]
+        pub fn fallback_uri(&self) -> Vec<u8> {
+            self.fallback_uri.clone()
+        }
+
+        #[ink(message)]
+        pub fn set_fallback_uri(&mut self, uri: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.fallback_uri = uri;
+
+            Ok(())
+        }
+
+        /// Returns the base uri template shared by tokens without their own uri.
+        #[ink(message)]
+        pub fn base_uri(&self) -> Vec<u8> {
+            self.base_uri.clone()
+        }
+
+        #[ink(message)]
+        pub fn set_base_uri(&mut self, uri: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.base_uri = uri;
+
+            Ok(())
+        }
+
+        /// Tells indexers to refetch the metadata of ids `from_id` through `to_id`, for example
+        /// after a reveal replaced the placeholder art.
+        #[ink(message)]
+        pub fn refresh_metadata(&mut self, from_id: TokenId, to_id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if from_id > to_id {
+                return Err(Error::InvalidRange);
+            }
+
+            self.env().emit_event(BatchMetadataUpdate {
+                from_id,
+                to_id,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the uri of the collection-level metadata.
+        #[ink(message)]
+        pub fn contract_uri(&self) -> Option<Vec<u8>> {
+            self.contract_uri.clone()
+        }
+
+        #[ink(message)]
+        pub fn set_contract_uri(&mut self, uri: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.contract_uri = Some(uri);
+
+            Ok(())
+        }
+
+        /// Returns the number of decimals wallets should use to display amounts of token `id`,
+        /// or 0 if none were set.
+        #[ink(message)]
+        pub fn decimals_of(&self, id: TokenId) -> u8 {
+            *self.decimals.get(&id).unwrap_or(&0)
+        }
+
+        /// Sets the number of decimals of token `id`.
+        /// Emits a {BatchMetadataUpdate} event for `id`.
+        #[ink(message)]
+        pub fn set_decimals(&mut self, id: TokenId, decimals: u8) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.decimals.insert(id, decimals);
+
+            self.env().emit_event(BatchMetadataUpdate {
+                from_id: id,
+                to_id: id,
+            });
+
+            Ok(())
+        }
+
+        /// Caps how many tokens of type `id` can ever be minted to a single wallet.
+        #[ink(message)]
+        pub fn set_max_mint_per_wallet(&mut self, id: TokenId, cap: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_creator(caller, id) {
+                return Err(Error::OnlyCreator);
+            }
+
+            self.max_mint_per_wallet.insert(id, cap);
+
+            Ok(())
+        }
+
+        /// Returns the per-wallet mint cap of token `id`, if any.
+        #[ink(message)]
+        pub fn max_mint_per_wallet_of(&self, id: TokenId) -> Option<TokenBalance> {
+            self.max_mint_per_wallet.get(&id).cloned()
+        }
+
+        /// Returns how many tokens of type `id` have been minted to `account`.
+        #[ink(message)]
+        pub fn minted_by_wallet_of(&self, account: AccountId, id: TokenId) -> TokenBalance {
+            *self.minted_by_wallet.get(&(account, id)).unwrap_or(&0)
+        }
+
+        /// Get the balance of an account's Tokens
+        #[ink(message)]
+        pub fn balance_of(&self, account: AccountId, id: TokenId) -> TokenBalance {
+            self.balance_of_or_zero(&account, &id)
+        }
+
+        /// Returns all token types currently held by `account`.
+        #[ink(message)]
+        pub fn tokens_of(&self, account: AccountId) -> Vec<TokenId> {
+            self.owned_tokens.get(&account).cloned().unwrap_or_default()
+        }
+
+        /// Returns the number of distinct token types that have ever been minted.
+        /// Unlike the PSP37 collection supply, burning a type's whole supply does not lower it.
+        #[ink(message)]
+        pub fn token_type_count(&self) -> u32 {
+            self.token_type_count
+        }
+
+        /// Returns the number of token types ever minted, the number of accounts currently
+        /// holding any token, and whether any token id is paused by `pause_id`.
+        #[ink(message)]
+        pub fn stats(&self) -> (u32, u32, bool) {
+            // `unpause_id` removes the entry, so every remaining one is a paused id.
+            (self.token_type_count, self.holder_count, !self.paused_ids.is_empty())
+        }
+
+        /// Returns the sum of ``account``'s balances across every token type it holds, capped at
+        /// `TokenBalance::MAX`. The cost grows with the number of distinct ids the account holds.
+        #[ink(message)]
+        pub fn total_balance_of(&self, account: AccountId) -> TokenBalance {
+            self.owned_tokens.get(&account)
+                .map(|tokens| tokens.as_slice())
+                .unwrap_or(&[])
+                .iter()
+                .fold(0, |total: TokenBalance, id| total.saturating_add(self.balance_of_or_zero(&account, id)))
+        }
+
+        /// Returns ``account``'s balance of token `id` as a share of its total supply, in basis points.
+        /// Returns 0 when nothing of `id` is in circulation.
+        #[ink(message)]
+        pub fn supply_share_bps(&self, account: AccountId, id: TokenId) -> u16 {
+            let supply = self.supply_of_or_zero(&id);
+            if supply == 0 {
+                return 0;
+            }
+
+            let balance = self.balance_of_or_zero(&account, &id);
+            let share = match balance.checked_mul(10_000) {
+                Some(scaled) => scaled / supply,
+                None => balance / (supply / 10_000),
+            };
+
+            share as u16
+        }
+
+        /// Get the balance of multiple account/token pairs
+        #[ink(message)]
+        pub fn balance_of_batch(&self, accounts: Vec<AccountId>, ids: Vec<TokenId>) -> Result<Vec<TokenBalance>, Error> {
+            if accounts.len() != ids.len() {
+                return Err(Error::InvalidArrayLength(accounts.len() as u32, ids.len() as u32));
+            }
+
+            if ids.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge);
+            }
+
+            let mut batch_balances: Vec<TokenBalance> = Vec::new();
+
+            for i in 0..accounts.len() {
+                batch_balances.push(self.balance_of_or_zero(&accounts[i], &ids[i]));
+            }
+
+            Ok(batch_balances)
+        }
+
+        /// Returns `(balance of account, total supply)` for each of `ids`, in order.
+        #[ink(message)]
+        pub fn balances_and_supplies(&self, account: AccountId, ids: Vec<TokenId>) -> Vec<(TokenBalance, TokenBalance)> {
+            ids.iter()
+                .map(|id| (self.balance_of_or_zero(&account, id), self.supply_of_or_zero(id)))
+                .collect()
+        }
+
+        /// Returns the balance of an account's Tokens together with the current block number,
+        /// so that an off-chain attestation can record when the value was read.
+        #[ink(message)]
+        pub fn balance_attestation(&self, account: AccountId, id: TokenId) -> (TokenBalance, BlockNumber) {
+            (self.balance_of_or_zero(&account, &id), self.env().block_number())
+        }
+
+        /// Takes a snapshot of all balances and returns its id. Balances are only copied once they
+        /// change after the snapshot, so taking one does not touch any account.
+        #[ink(message)]
+        pub fn snapshot(&mut self) -> Result<u32, Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.snapshot_id += 1;
+
+            Ok(self.snapshot_id)
+        }
+
+        /// Returns ``account``'s balance of token `id` at the time snapshot `snapshot_id` was taken.
+        #[ink(message)]
+        pub fn balance_of_at(&self, account: AccountId, id: TokenId, snapshot_id: u32) -> Result<TokenBalance, Error> {
+            if snapshot_id == 0 || snapshot_id > self.snapshot_id {
+                return Err(Error::InvalidSnapshot);
+            }
+
+            // The first checkpoint written at or after the snapshot holds the balance it saw;
+            // without one the balance has not changed since.
+            let checkpoint = self.balance_checkpoints.get(&(account, id))
+                .and_then(|checkpoints| checkpoints.iter().find(|(checkpoint_id, _)| *checkpoint_id >= snapshot_id));
+
+            match checkpoint {
+                Some((_, balance)) => Ok(*balance),
+                None => Ok(self.balance_of_or_zero(&account, &id)),
+            }
+        }
+
+        /// Grants or revokes permission to `operator` to transfer the caller's tokens, according to `approved`.
+        /// Returns whether `operator` was approved before the call.
+        /// Emits an {ApprovalForAll} event.
+        #[ink(message)]
+        pub fn set_approval_for_all(&mut self, operator: AccountId, approved: bool) -> Result<bool, Error> {
+            let caller = self.env().caller();
+
+            self.ensure_not_self_approval(&caller, &operator)?;
+            self.ensure_operator_allowed(&operator, approved)?;
+            self.ensure_operator_capacity(&caller, &[operator], approved)?;
+
+            let previous = self.approved_for_all(&caller, &operator);
+            self.set_operator_approval(caller, operator, approved, None);
+
+            Ok(previous)
+        }
+
+        /// Like `set_approval_for_all`, but the approval lapses once the block timestamp
+        /// passes `expires_at`.
+        /// Emits an {ApprovalForAll} event.
+        #[ink(message)]
+        pub fn set_approval_for_all_until(&mut self, operator: AccountId, approved: bool, expires_at: Timestamp) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.ensure_not_self_approval(&caller, &operator)?;
+            self.ensure_operator_allowed(&operator, approved)?;
+            self.ensure_operator_capacity(&caller, &[operator], approved)?;
+
+            self.set_operator_approval(caller, operator, approved, Some(expires_at));
+
+            Ok(())
+        }
+
+        /// Applies `approved` to each of `operators` for the caller's tokens, emitting one
+        /// {ApprovalForAll} event per operator. Nothing is changed if any entry is the caller.
+        #[ink(message)]
+        pub fn set_approval_for_all_batch(&mut self, operators: Vec<AccountId>, approved: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            for operator in operators.iter() {
+                self.ensure_not_self_approval(&caller, operator)?;
+                self.ensure_operator_allowed(operator, approved)?;
+            }
+            self.ensure_operator_capacity(&caller, &operators, approved)?;
+
+            for operator in operators {
+                self.set_operator_approval(caller, operator, approved, None);
+            }
+
+            Ok(())
+        }
+
+        /// Revokes every operator of the caller, including default operators that were not
+        /// revoked yet. Emits an {ApprovalForAll} event for each.
+        #[ink(message)]
+        pub fn revoke_all_operators(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            let mut operators = self.approved_operators.take(&caller).unwrap_or_default();
+            for operator in self.default_operators.keys() {
+                if self.approved_for_all(&caller, operator) && !operators.contains(operator) {
+                    operators.push(*operator);
+                }
+            }
+
+            for operator in operators {
+                self.set_operator_approval(caller, operator, false, None);
+            }
+
+            Ok(())
+        }
+
+        /// Grants or revokes permission to `operator` to transfer ``owner``'s tokens on behalf of `owner`,
+        /// who signed the Blake2x256 hash of the SCALE-encoded
+        /// `(PERMIT_DOMAIN, contract, owner, operator, approved, nonce, deadline)` off-chain with the
+        /// ed25519 key behind their account. Binding the contract's own address keeps a permit from
+        /// being replayed against another deployment.
+        /// Lets a relayer submit the approval so the owner does not need a transaction of their own.
+        /// Emits an {ApprovalForAll} event.
+        #[ink(message)]
+        pub fn permit(&mut self, owner: AccountId, operator: AccountId, approved: bool, nonce: u128, deadline: Timestamp, signature: [u8; 64]) -> Result<(), Error> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+
+            let current_nonce = self.nonce_or_zero(&owner);
+            if nonce < current_nonce {
+                return Err(Error::NonceAlreadyUsed);
+            }
+            if nonce != current_nonce {
+                return Err(Error::InvalidNonce);
+            }
+
+            self.ensure_not_self_approval(&owner, &operator)?;
+            self.ensure_operator_allowed(&operator, approved)?;
+            self.ensure_operator_capacity(&owner, &[operator], approved)?;
+
+            let contract = self.env().account_id();
+            let message_hash = self.env().hash_encoded::<Blake2x256, _>(&(PERMIT_DOMAIN, contract, owner, operator, approved, nonce, deadline));
+            if !verify_signature(&owner, &signature, &message_hash) {
+                return Err(Error::InvalidSignature);
+            }
+
+            self.nonces.insert(owner, nonce + 1);
+            self.set_operator_approval(owner, operator, approved, None);
+
+            Ok(())
+        }
+
+        /// Returns the nonce ``account``'s next `permit` signature must be made over.
+        #[ink(message)]
+        pub fn nonce_of(&self, account: AccountId) -> u128 {
+            self.nonce_or_zero(&account)
+        }
+
+        /// Invalidates ``account``'s outstanding `permit` signatures. Signatures are only accepted
+        /// over the current nonce, so moving it on by one rejects every signature made so far.
+        /// Emits a {NoncesInvalidated} event.
+        #[ink(message)]
+        pub fn invalidate_nonces(&mut self, account: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            let nonce = self.nonce_or_zero(&account) + 1;
+            self.nonces.insert(account, nonce);
+
+            self.env().emit_event(NoncesInvalidated {
+                account,
+                nonce,
+            });
+
+            Ok(())
+        }
+
+        /// Mints `value` of `id` to `to` against a voucher the contract owner signed off-chain with the
+        /// ed25519 key behind their account, over the Blake2x256 hash of the SCALE-encoded
+        /// `(VOUCHER_DOMAIN, contract, to, id, value, price, nonce)`, so the buyer rather than the
+        /// creator pays for the mint. A voucher only redeems on the contract address it was signed
+        /// for, and none redeem once ownership is renounced. At least `price` must be sent with the
+        /// call; the whole payment goes to the owner. Each `nonce` can be redeemed once, and the
+        /// owner can revoke an unredeemed one with `revoke_voucher`.
+        #[ink(message, payable)]
+        pub fn mint_voucher(&mut self, to: AccountId, id: TokenId, value: TokenBalance, price: Balance, nonce: u128, signature: [u8; 64]) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let payment = self.env().transferred_balance();
+
+            if self.redeemed_vouchers.contains_key(&nonce) {
+                return Err(Error::VoucherAlreadyRedeemed);
+            }
+
+            if payment < price {
+                return Err(Error::InsufficientPayment);
+            }
+
+            // A renounced owner is the zero account, which signs nothing and must not be paid.
+            if is_zero_account(&self.owner) {
+                return Err(Error::InvalidVoucherSignature);
+            }
+
+            let contract = self.env().account_id();
+            let message_hash = self.env().hash_encoded::<Blake2x256, _>(&(VOUCHER_DOMAIN, contract, to, id, value, price, nonce));
+            if !verify_signature(&self.owner, &signature, &message_hash) {
+                return Err(Error::InvalidVoucherSignature);
+            }
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            if !self.is_registered(id) {
+                return Err(Error::TokenNotFound);
+            }
+
+            self.ensure_mintable(&to, &id, value)?;
+
+            if payment > 0 {
+                self.env().transfer(self.owner, payment).map_err(|_| Error::NativeTransferFailed)?;
+            }
+
+            self.redeemed_vouchers.insert(nonce, true);
+            self.mint_token_to(&to, &id, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to,
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Revokes the unredeemed voucher with `nonce`, for example after it leaked, so it can no
+        /// longer be redeemed. Emits a {VoucherRevoked} event.
+        #[ink(message)]
+        pub fn revoke_voucher(&mut self, nonce: u128) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if self.redeemed_vouchers.contains_key(&nonce) {
+                return Err(Error::VoucherAlreadyRedeemed);
+            }
+
+            self.redeemed_vouchers.insert(nonce, true);
+
+            self.env().emit_event(VoucherRevoked {
+                nonce,
+            });
+
+            Ok(())
+        }
+
+        /// Adds `operator` to or removes it from the allowlist of operators accounts may approve.
+        /// The same allowlist names the royalty-honoring operators while royalties are enforced.
+        #[ink(message)]
+        pub fn set_operator_allowed(&mut self, operator: AccountId, allowed: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if allowed {
+                self.operator_allowlist.insert(operator, true);
+            } else {
+                self.operator_allowlist.take(&operator);
+            }
+
+            Ok(())
+        }
+
+        /// Returns true if `operator` is on the operator allowlist.
+        #[ink(message)]
+        pub fn is_operator_allowed(&self, operator: AccountId) -> bool {
+            *self.operator_allowlist.get(&operator).unwrap_or(&false)
+        }
+
+        /// Turns enforcement of the operator allowlist on or off. While it is on, only allowlisted
+        /// operators can be approved; revoking an approval is always possible.
+        #[ink(message)]
+        pub fn set_enforce_allowlist(&mut self, enforce: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.enforce_allowlist = enforce;
+
+            Ok(())
+        }
+
+        /// Returns true if the operator allowlist is enforced.
+        #[ink(message)]
+        pub fn enforces_allowlist(&self) -> bool {
+            self.enforce_allowlist
+        }
+
+        /// Turns royalty enforcement on or off. While it is on, a transfer made by anyone other
+        /// than the holder of the tokens fails unless the caller is on the operator allowlist.
+        #[ink(message)]
+        pub fn set_enforce_royalties(&mut self, enforce: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.enforce_royalties = enforce;
+
+            Ok(())
+        }
+
+        /// Returns true if royalties are enforced.
+        #[ink(message)]
+        pub fn enforces_royalties(&self) -> bool {
+            self.enforce_royalties
+        }
+
+        /// Stops or resumes the granting of new operator approvals, for example during an
+        /// incident. Revocations and transfers by already approved operators keep working.
+        #[ink(message)]
+        pub fn set_approvals_paused(&mut self, paused: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.approvals_paused = paused;
+
+            Ok(())
+        }
+
+        /// Returns true if new operator approvals are currently paused.
+        #[ink(message)]
+        pub fn approvals_paused(&self) -> bool {
+            self.approvals_paused
+        }
+
+        /// Adds or removes `operator` from the operators every account approves by default.
+        /// Accounts can still revoke a default operator with `set_approval_for_all`. At most
+        /// `MAX_OPERATORS_PER_ACCOUNT` default operators can be set at a time.
+        #[ink(message)]
+        pub fn set_default_operator(&mut self, operator: AccountId, enabled: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if enabled && !self.default_operators.contains_key(&operator)
+                && self.default_operators.len() as usize >= MAX_OPERATORS_PER_ACCOUNT {
+                return Err(Error::TooManyOperators);
+            }
+
+            if enabled {
+                self.default_operators.insert(operator, true);
+            } else {
+                self.default_operators.take(&operator);
+            }
+
+            Ok(())
+        }
+
+        /// Returns true if `operator` is approved by default for every account.
+        #[ink(message)]
+        pub fn is_default_operator(&self, operator: AccountId) -> bool {
+            *self.default_operators.get(&operator).unwrap_or(&false)
+        }
+
+        /// Returns true if `operator` is approved to transfer ``account``'s tokens.
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, account: AccountId, operator: AccountId) -> bool {
+            self.approved_for_all(&account, &operator)
+        }
+
+        /// Returns `is_approved_for_all(owners[i], operators[i])` for each pair.
+        #[ink(message)]
+        pub fn are_approved_for_all(&self, owners: Vec<AccountId>, operators: Vec<AccountId>) -> Result<Vec<bool>, Error> {
+            if owners.len() != operators.len() {
+                return Err(Error::InvalidArrayLength(owners.len() as u32, operators.len() as u32));
+            }
+
+            Ok(owners
+                .iter()
+                .zip(operators.iter())
+                .map(|(owner, operator)| self.approved_for_all(owner, operator))
+                .collect())
+        }
+
+        /// Returns the operators `account` has explicitly approved and whose approval has not expired.
+        /// Default operators are not listed unless `account` approved them itself.
+        #[ink(message)]
+        pub fn operators_of(&self, account: AccountId) -> Vec<AccountId> {
+            self.approved_operators
+                .get(&account)
+                .map(|operators| {
+                    operators
+                        .iter()
+                        .filter(|operator| self.approved_for_all(&account, operator))
+                        .copied()
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        /// Raises ``spender``'s allowance over the caller's `id` by `delta`, instead of
+        /// overwriting it the way `approve` does.
+        #[ink(message)]
+        pub fn increase_allowance(&mut self, spender: AccountId, id: TokenId, delta: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.ensure_not_self_approval(&caller, &spender)?;
+            self.ensure_operator_allowed(&spender, delta > 0)?;
+
+            let allowance = *self.allowances.get(&(caller, spender, id)).unwrap_or(&0);
+            let value = allowance.checked_add(delta).ok_or(Error::AllowanceOverflow)?;
+            self.allowances.insert((caller, spender, id), value);
+
+            self.env().emit_event(Approval {
+                owner: caller,
+                operator: spender,
+                id: Some(id),
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Lowers ``spender``'s allowance over the caller's `id` by `delta`, stopping at zero.
+        #[ink(message)]
+        pub fn decrease_allowance(&mut self, spender: AccountId, id: TokenId, delta: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            let allowance = *self.allowances.get(&(caller, spender, id)).unwrap_or(&0);
+            let value = allowance.saturating_sub(delta);
+            self.allowances.insert((caller, spender, id), value);
+
+            self.env().emit_event(Approval {
+                owner: caller,
+                operator: spender,
+                id: Some(id),
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Transfers `value` tokens of token type `id` from `from` to `to`.
+        #[ink(message)]
+        pub fn safe_transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if is_zero_account(&from) || is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if !self.approved_or_owner(from, caller) {
+                return Err(Error::NotApproved);
+            }
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            let received = self.transfer_token_from(&from, &to, &id, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from,
+                to,
+                id,
+                value: received,
+            });
+
+            Ok(())
+        }
+
+        /// Like `safe_transfer_from`, but when the caller moves the tokens as an operator of `from`
+        /// its approval is revoked right after, so a marketplace does not keep standing access once
+        /// a sale settles. Emits the transfer's {TransferSingle} and then an {ApprovalForAll} event.
+        #[ink(message)]
+        pub fn transfer_and_revoke(&mut self, from: AccountId, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            self.safe_transfer_from(from, to, id, value)?;
+
+            if caller != from {
+                self.set_operator_approval(from, caller, false, None);
+            }
+
+            Ok(())
+        }
+
+        /// Swaps `give_value` of the caller's `give_id` for `want_value` of `counterparty`'s
+        /// `want_id` in one call. Calling it is the caller's consent; `counterparty` consents by
+        /// having approved the caller as an operator beforehand. Both legs are checked before
+        /// either moves, so a failing leg leaves both balances untouched.
+        /// Emits a {TransferSingle} event for each leg.
+        #[ink(message)]
+        pub fn swap(&mut self, counterparty: AccountId, give_id: TokenId, give_value: TokenBalance, want_id: TokenId, want_value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if counterparty == caller || !self.approved_or_owner(counterparty, caller) {
+                return Err(Error::NotApproved);
+            }
+
+            if give_value == 0 || want_value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            self.ensure_transferable(&caller, &counterparty, &give_id, give_value)?;
+            self.ensure_transferable(&counterparty, &caller, &want_id, want_value)?;
+
+            let given = self.move_token(&caller, &counterparty, &give_id, give_value)?;
+            let received = self.move_token(&counterparty, &caller, &want_id, want_value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: caller,
+                to: counterparty,
+                id: give_id,
+                value: given,
+            });
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: counterparty,
+                to: caller,
+                id: want_id,
+                value: received,
+            });
+
+            Ok(())
+        }
+
+        /// Send multiple types of Tokens from `from` to `to`.
+        ///
+        /// A zero entry in `values` rejects the whole batch, so no partial transfer happens.
+        /// An id listed more than once moves the sum of its values, and the balance must cover
+        /// that sum.
+        #[ink(message)]
+        pub fn safe_batch_transfer_from(&mut self, from: AccountId, to: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.batch_transfer_from(caller, from, to, ids, values)
+        }
+
+        /// Approves `to` as an operator of the caller and has it pull multiple types of the
+        /// caller's tokens in the same call, for marketplace settlement. The approval passes the
+        /// same checks as `set_approval_for_all` and is revoked again before the call returns,
+        /// whether or not the transfer succeeded; an approval `to` already held is left as it was.
+        /// `data` is accepted like the PSP37 transfers' and, as there, not yet handed to a receiver hook.
+        /// Emits the transfer's {TransferBatch} between the {ApprovalForAll} events of the approval.
+        #[ink(message)]
+        pub fn approve_and_transfer(&mut self, to: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>, _data: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            self.ensure_not_self_approval(&caller, &to)?;
+
+            let already_approved = self.approved_for_all(&caller, &to);
+            if !already_approved {
+                self.ensure_operator_allowed(&to, true)?;
+                self.ensure_operator_capacity(&caller, &[to], true)?;
+                self.set_operator_approval(caller, to, true, None);
+            }
+
+            let result = self.batch_transfer_from(to, caller, to, ids, values);
+
+            if !already_approved {
+                self.set_operator_approval(caller, to, false, None);
+            }
+
+            result
+        }
+
+        /// Lists `marketplace` with a protocol fee of `fee_bps` basis points, or delists it when `None`.
+        #[ink(message)]
+        pub fn set_marketplace(&mut self, marketplace: AccountId, fee_bps: Option<u16>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            match fee_bps {
+                Some(fee_bps) => {
+                    if fee_bps > 10_000 {
+                        return Err(Error::InvalidFee);
+                    }
+                    self.marketplaces.insert(marketplace, fee_bps);
+                }
+                None => {
+                    self.marketplaces.take(&marketplace);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Returns the protocol fee of `marketplace` in basis points, if it is listed.
+        #[ink(message)]
+        pub fn marketplace_fee_bps(&self, marketplace: AccountId) -> Option<u16> {
+            self.marketplaces.get(&marketplace).cloned()
+        }
+
+        /// Sets the account receiving marketplace protocol fees.
+        #[ink(message)]
+        pub fn set_treasury(&mut self, treasury: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.treasury = treasury;
+
+            Ok(())
+        }
+
+        /// Returns the account receiving marketplace protocol fees.
+        #[ink(message)]
+        pub fn treasury(&self) -> AccountId {
+            self.treasury
+        }
+
+        /// Settles a sale made on a listed marketplace: transfers `value` tokens of type `id`
+        /// from `from` to `to` and forwards the marketplace's share of `sale_price`, paid along
+        /// with the call, to the treasury. Anything paid above that share is refunded to the
+        /// marketplace.
+        /// Emits a {TransferSingle} and a {MarketplaceSale} event.
+        #[ink(message, payable)]
+        pub fn market_transfer(&mut self, from: AccountId, to: AccountId, id: TokenId, value: TokenBalance, sale_price: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            let fee_bps = self.marketplaces.get(&caller).cloned().ok_or(Error::NotApprovedMarketplace)?;
+
+            if is_zero_account(&from) || is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if !self.approved_or_owner(from, caller) {
+                return Err(Error::NotApproved);
+            }
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            let fee = sale_price.checked_mul(Balance::from(fee_bps))
+                .map(|fee| fee / 10_000)
+                .ok_or(Error::FeeOverflow)?;
+            let payment = self.env().transferred_balance();
+            if payment < fee {
+                return Err(Error::InsufficientFee);
+            }
+
+            let received = self.transfer_token_from(&from, &to, &id, value)?;
+
+            if fee > 0 {
+                self.env().transfer(self.treasury, fee).map_err(|_| Error::NativeTransferFailed)?;
+            }
+            if payment > fee {
+                self.env().transfer(caller, payment - fee).map_err(|_| Error::NativeTransferFailed)?;
+            }
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from,
+                to,
+                id,
+                value: received,
+            });
+
+            self.env().emit_event(MarketplaceSale {
+                marketplace: caller,
+                from,
+                to,
+                id,
+                value,
+                sale_price,
+                fee,
+            });
+
+            Ok(())
+        }
+
+        /// Sets the fee taken from every transfer in basis points. The fee is moved to
+        /// `fee_recipient`, or burned when it is `None`, and `to` receives the remainder.
+        #[ink(message)]
+        pub fn set_transfer_fee(&mut self, fee_bps: u16, fee_recipient: Option<AccountId>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if fee_bps > 10_000 {
+                return Err(Error::InvalidFee);
+            }
+
+            self.transfer_fee_bps = fee_bps;
+            self.fee_recipient = fee_recipient;
+
+            Ok(())
+        }
+
+        /// Returns the fee taken from every transfer in basis points.
+        #[ink(message)]
+        pub fn transfer_fee_bps(&self) -> u16 {
+            self.transfer_fee_bps
+        }
+
+        /// Returns the account receiving transfer fees, or `None` if they are burned.
+        #[ink(message)]
+        pub fn fee_recipient(&self) -> Option<AccountId> {
+            self.fee_recipient
+        }
+
+        /// Sets the native fee charged per token minted through `mint`, `mint_batch` and `claim`.
+        /// The fee goes to the `fee_recipient`, or to the treasury when none is set. The owner's
+        /// own mints (`mint_with_uri`, `mint_batch_with_uris`, `create_token`) are not charged.
+        #[ink(message)]
+        pub fn set_mint_fee(&mut self, mint_fee: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.mint_fee = mint_fee;
+
+            Ok(())
+        }
+
+        /// Returns the native fee charged per token minted through `mint`, `mint_batch` and `claim`.
+        #[ink(message)]
+        pub fn mint_fee(&self) -> Balance {
+            self.mint_fee
+        }
+
+        /// Mints `WRAPPED_NATIVE_ID` to the caller for the native value sent with the call.
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let value = self.env().transferred_balance();
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            if self.frozen_or_false(&caller) {
+                return Err(Error::AccountFrozen);
+            }
+
+            self.issue_token_to(&caller, &WRAPPED_NATIVE_ID, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to: caller,
+                id: WRAPPED_NATIVE_ID,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Burns `value` of the caller's `WRAPPED_NATIVE_ID` and sends the same amount of native
+        /// token back to the caller.
+        #[ink(message)]
+        pub fn withdraw(&mut self, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            if self.frozen_or_false(&caller) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if self.paused_or_false(&WRAPPED_NATIVE_ID) {
+                return Err(Error::TokenPaused);
+            }
+
+            self.burn_token_from(&caller, &WRAPPED_NATIVE_ID, value)?;
+            self.env().transfer(caller, value).map_err(|_| Error::NativeTransferFailed)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: caller,
+                to: AccountId::from(ZERO_ACCOUNT),
+                id: WRAPPED_NATIVE_ID,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Stakes `value` of the caller's tokens of type `id`, moving them out of the
+        /// transferable balance until they are unlocked again.
+        #[ink(message)]
+        pub fn lock(&mut self, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            self.ensure_lockable(&caller, &id)?;
+
+            let balance = self.balance_of_or_zero(&caller, &id);
+            if balance < value {
+                return Err(Error::InsufficientBalance { id, available: balance, required: value });
+            }
+            let locked = self.locked_balance_of(caller, id).checked_add(value).ok_or(Error::SupplyOverflow)?;
+
+            // The tokens stay the caller's, so the holder index is left alone.
+            self.write_checkpoint(&caller, &id);
+            self.balances.insert(Self::key_of(BALANCES_PREFIX, &(caller, id)), balance - value);
+            self.locked_balances.insert((caller, id), locked);
+
+            self.env().emit_event(Locked {
+                account: caller,
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Unstakes `value` of the caller's locked tokens of type `id` back into the transferable balance.
+        #[ink(message)]
+        pub fn unlock(&mut self, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            self.ensure_lockable(&caller, &id)?;
+
+            let locked = self.locked_balance_of(caller, id);
+            if locked < value {
+                return Err(Error::InsufficientBalance { id, available: locked, required: value });
+            }
+            let balance = self.balance_of_or_zero(&caller, &id);
+
+            if locked == value {
+                self.locked_balances.take(&(caller, id));
+            } else {
+                self.locked_balances.insert((caller, id), locked - value);
+            }
+            self.write_checkpoint(&caller, &id);
+            self.balances.insert(Self::key_of(BALANCES_PREFIX, &(caller, id)), balance + value);
+
+            self.env().emit_event(Unlocked {
+                account: caller,
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the amount of ``account``'s tokens of type `id` that are staked.
+        #[ink(message)]
+        pub fn locked_balance_of(&self, account: AccountId, id: TokenId) -> TokenBalance {
+            *self.locked_balances.get(&(account, id)).unwrap_or(&0)
+        }
+
+        /// Burns `value` of the caller's tokens of type `id` and mints the same amount of the
+        /// wrapper ERC20 to the caller.
+        #[ink(message)]
+        pub fn wrap(&mut self, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            let wrapper = self.wrapper_for(id)?;
+
+            // The tokens leave this contract's control, so wrapping is held to the same freezes,
+            // pauses and locks as a transfer.
+            self.ensure_transferable(&caller, &AccountId::from(ZERO_ACCOUNT), &id, value)?;
+
+            self.retire_token_from(&caller, &id, value)?;
+            self.mark_active(&caller);
+
+            if let Err(error) = self.call_wrapper(wrapper, WRAPPER_MINT_SELECTOR, caller, value) {
+                // Nothing was minted on the other side, so the caller gets the tokens back.
+                self.reissue_token_to(&caller, &id, value)?;
+                return Err(error);
+            }
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: caller,
+                to: AccountId::from(ZERO_ACCOUNT),
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Burns `value` of the caller's wrapper ERC20 and gives back the same amount of
+        /// tokens of type `id`.
+        #[ink(message)]
+        pub fn unwrap(&mut self, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            let wrapper = self.wrapper_for(id)?;
+
+            if self.paused_or_false(&id) {
+                return Err(Error::TokenPaused);
+            }
+
+            if self.frozen_or_false(&caller) {
+                return Err(Error::AccountFrozen);
+            }
+
+            // The tokens already counted against the caps when they were first minted,
+            // so they are re-issued without going through `mint_token_to`. That also keeps
+            // unwrapping open after `finalize_minting`, as it brings back existing supply.
+            self.reissue_token_to(&caller, &id, value)?;
+
+            if let Err(error) = self.call_wrapper(wrapper, WRAPPER_BURN_SELECTOR, caller, value) {
+                // Nothing was burned on the other side, so the re-issued tokens are taken back.
+                self.retire_token_from(&caller, &id, value)?;
+                return Err(error);
+            }
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to: caller,
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the wrapper ERC20 contract and the token type it wraps, if any.
+        #[ink(message)]
+        pub fn wrapper(&self) -> Option<(AccountId, TokenId)> {
+            self.wrapper
+        }
+
+        /// Allows or blocks transfers to this contract's own account. They are blocked by default
+        /// because nothing but `recover` can move tokens out of it again.
+        #[ink(message)]
+        pub fn set_accept_contract_transfers(&mut self, accept: bool) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.accept_contract_transfers = accept;
+
+            Ok(())
+        }
+
+        /// Returns true if transfers to this contract's own account are allowed.
+        #[ink(message)]
+        pub fn accepts_contract_transfers(&self) -> bool {
+            self.accept_contract_transfers
+        }
+
+        /// Moves `value` tokens of type `id` that were sent to this contract's own account by
+        /// mistake to `to`. Freezes and pauses still apply; the other transfer rules do not.
+        #[ink(message)]
+        pub fn recover(&mut self, id: TokenId, to: AccountId, value: TokenBalance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if self.paused_or_false(&id) {
+                return Err(Error::TokenPaused);
+            }
+
+            let contract = self.env().account_id();
+            self.remove_token_from(&contract, &id, value)?;
+            self.add_token_to(&to, &id, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: contract,
+                to,
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Sets the token type minted alongside every `mint`, in the same amount, or disables it when `None`.
+        #[ink(message)]
+        pub fn set_companion_id(&mut self, companion_id: Option<TokenId>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            self.companion_id = companion_id;
+
+            Ok(())
+        }
+
+        /// Returns the token type minted alongside every `mint`, if any.
+        #[ink(message)]
+        pub fn companion_id(&self) -> Option<TokenId> {
+            self.companion_id
+        }
+
+        /// Creates `value` tokens of token type `id`, and assigns them to `account`.
+        /// Returns the balance of `to` in `id` after the mint.
+        ///
+        /// While a mint fee is set, at least `mint_fee * value` must be sent with the call; the
+        /// fee is forwarded and any excess refunded to the caller before anything is minted.
+        #[ink(message, payable)]
+        pub fn mint(&mut self, to: AccountId, id: TokenId, value: TokenBalance) -> Result<TokenBalance, Error> {
+            let caller = self.env().caller();
+
+            if !self.is_minter(caller, id) {
+                return Err(Error::OnlyCreator);
+            }
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            self.ensure_mintable(&to, &id, value)?;
+            self.settle_mint_fee(caller, value)?;
+
+            self.mint_token_to(&to, &id, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to,
+                id,
+                value,
+            });
+
+            self.mint_companion_to(caller, to, id, value)?;
+
+            Ok(self.balance_of_or_zero(&to, &id))
+        }
+
+        /// Mints `value` tokens of type `id` to `to` that cannot be transferred before `unlock_at`.
+        /// Minting more locked tokens to the same account and id adds to the locked amount and
+        /// keeps the later of the two unlock times.
+        #[ink(message)]
+        pub fn mint_locked(&mut self, to: AccountId, id: TokenId, value: TokenBalance, unlock_at: Timestamp) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            // An unregistered id would later be handed out by `create` with supply already in it.
+            if !self.is_registered(id) {
+                return Err(Error::TokenNotFound);
+            }
+
+            self.mint_token_to(&to, &id, value)?;
+
+            let (locked, locked_until) = self.locks.get(&(to, id)).cloned().unwrap_or((0, 0));
+            self.locks.insert((to, id), (locked + value, core::cmp::max(locked_until, unlock_at)));
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to,
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the locked amount and unlock time recorded for ``account``'s tokens of type `id`.
+        #[ink(message)]
+        pub fn lock_of(&self, account: AccountId, id: TokenId) -> (TokenBalance, Timestamp) {
+            self.locks.get(&(account, id)).cloned().unwrap_or((0, 0))
+        }
+
+        /// A zero entry in `values` rejects the whole batch, so nothing is minted.
+        ///
+        /// Charges the mint fee on the sum of `values`, like `mint`.
+        #[ink(message, payable)]
+        pub fn mint_batch(&mut self, to: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if self.frozen_or_false(&to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if ids.len() != values.len() {
+                return Err(Error::InvalidArrayLength(ids.len() as u32, values.len() as u32));
+            }
+
+            if ids.is_empty() {
+                return Err(Error::EmptyBatch);
+            }
+
+            if ids.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge);
+            }
+
+            if values.iter().any(|value| *value == 0) {
+                return Err(Error::ZeroValue);
+            }
+
+            if ids.iter().any(|id| !self.is_minter(caller, *id)) {
+                return Err(Error::OnlyCreator);
+            }
+
+            let mut total: TokenBalance = 0;
+            for (id, value) in Self::totals_by_id(&ids, &values).iter() {
+                self.ensure_mintable(&to, id, *value)?;
+                // A sum past `TokenBalance::MAX` can only matter with a fee set, and then the fee
+                // for it cannot be paid anyway.
+                total = total.saturating_add(*value);
+            }
+
+            self.settle_mint_fee(caller, total)?;
+
+            for i in 0..ids.len() {
+                let id = ids[i];
+                let value = values[i];
+
+                self.mint_token_to(&to, &id, value)?;
+                self.mint_companion_to(caller, to, id, value)?;
+            }
+
+            self.env().emit_event(TransferBatch {
+                operator: caller,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to,
+                ids,
+                values,
+            });
+
+            Ok(())
+        }
+
+        /// Destroys `value` tokens of token type `id` from `account`
+        /// Returns the balance `from` has left in `id`.
+        #[ink(message)]
+        pub fn burn(&mut self, from: AccountId, id: TokenId, value: TokenBalance) -> Result<TokenBalance, Error> {
+            let caller = self.env().caller();
+
+            if !self.is_creator(caller, id) {
+                return Err(Error::OnlyCreator);
+            }
+
+            if is_zero_account(&from) {
+                return Err(Error::NotApproved);
+            }
+
+            if value == 0 {
+                return Err(Error::ZeroValue);
+            }
+
+            self.burn_token_from(&from, &id, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from,
+                to: AccountId::from(ZERO_ACCOUNT),
+                id,
+                value,
+            });
+
+            Ok(self.balance_of_or_zero(&from, &id))
+        }
+
+        /// A zero entry in `values` rejects the whole batch, so nothing is burned.
+        #[ink(message)]
+        pub fn burn_batch(&mut self, from: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if is_zero_account(&from) {
+                return Err(Error::NotApproved);
+            }
+
+            if ids.len() != values.len() {
+                return Err(Error::InvalidArrayLength(ids.len() as u32, values.len() as u32));
+            }
+
+            if ids.is_empty() {
+                return Err(Error::EmptyBatch);
+            }
+
+            if ids.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge);
+            }
+
+            if values.iter().any(|value| *value == 0) {
+                return Err(Error::ZeroValue);
+            }
+
+            for i in 0..ids.len() {
+                let id = ids[i];
+                let value = values[i];
+
+                if !self.is_creator(caller, id) {
+                    return Err(Error::OnlyCreator);
+                }
+
+                self.burn_token_from(&from, &id, value)?;
+            }
+
+            self.env().emit_event(TransferBatch {
+                operator: caller,
+                from,
+                to: AccountId::from(ZERO_ACCOUNT),
+                ids,
+                values,
+            });
+
+            Ok(())
+        }
+
+        /// Sends `values[i]` of `ids[i]` from `from` to `recipients[i]`, for example to hand out
+        /// varied rewards. Every entry passes the transfer checks, and the balance of `from` must
+        /// cover the sum of each id, before anything moves. Emits a `TransferSingle` followed by a `BatchSequence` for each entry.
+        #[ink(message)]
+        pub fn distribute(&mut self, from: AccountId, recipients: Vec<AccountId>, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if recipients.len() != ids.len() {
+                return Err(Error::InvalidArrayLength(recipients.len() as u32, ids.len() as u32));
+            }
+
+            if ids.len() != values.len() {
+                return Err(Error::InvalidArrayLength(ids.len() as u32, values.len() as u32));
+            }
+
+            if ids.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge);
+            }
+
+            if is_zero_account(&from) || recipients.iter().any(is_zero_account) {
+                return Err(Error::NotApproved);
+            }
+
+            if values.iter().any(|value| *value == 0) {
+                return Err(Error::ZeroValue);
+            }
+
+            if !self.approved_or_owner(from, caller) {
+                return Err(Error::NotApproved);
+            }
+
+            for i in 0..recipients.len() {
+                self.ensure_transferable(&from, &recipients[i], &ids[i], values[i])?;
+            }
+
+            let totals = Self::totals_by_id(&ids, &values);
+            for (id, value) in totals.iter() {
+                self.ensure_unlocked(&from, id, *value)?;
+                self.ensure_balance(&from, id, *value)?;
+            }
+
+            for i in 0..recipients.len() {
+                let received = self.transfer_token_from(&from, &recipients[i], &ids[i], values[i])?;
+
+                self.env().emit_event(TransferSingle {
+                    operator: caller,
+                    from,
+                    to: recipients[i],
+                    id: ids[i],
+                    value: received,
+                });
+                self.env().emit_event(BatchSequence {
+                    operator: caller,
+                    seq: i as u32,
+                    batch_len: recipients.len() as u32,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Burns `values[i]` of `ids[i]` from `accounts[i]`, for example to clear a season's items.
+        /// Every entry is checked before anything is burned, so one failing entry aborts the whole call.
+        #[ink(message)]
+        pub fn admin_burn(&mut self, accounts: Vec<AccountId>, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if !self.is_owner(caller) {
+                return Err(Error::OnlyOwner);
+            }
+
+            if accounts.len() != ids.len() {
+                return Err(Error::InvalidArrayLength(accounts.len() as u32, ids.len() as u32));
+            }
+
+            if ids.len() != values.len() {
+                return Err(Error::InvalidArrayLength(ids.len() as u32, values.len() as u32));
+            }
+
+            if accounts.iter().any(is_zero_account) {
+                return Err(Error::NotApproved);
+            }
+
+            if values.iter().any(|value| *value == 0) {
+                return Err(Error::ZeroValue);
+            }
+
+            let mut totals: Vec<(AccountId, TokenId, TokenBalance)> = Vec::new();
+            for i in 0..accounts.len() {
+                match totals.iter_mut().find(|(account, id, _)| *account == accounts[i] && *id == ids[i]) {
+                    Some((_, _, total)) => {
+                        *total = total.saturating_add(values[i]);
+                    }
+                    None => totals.push((accounts[i], ids[i], values[i])),
+                }
+            }
+
+            for (account, id, total) in totals.iter() {
+                self.ensure_balance(account, id, *total)?;
+            }
+
+            for i in 0..accounts.len() {
+                self.burn_token_from(&accounts[i], &ids[i], values[i])?;
+
+                self.env().emit_event(TransferSingle {
+                    operator: caller,
+                    from: accounts[i],
+                    to: AccountId::from(ZERO_ACCOUNT),
+                    id: ids[i],
+                    value: values[i],
+                });
+                self.env().emit_event(BatchSequence {
+                    operator: caller,
+                    seq: i as u32,
+                    batch_len: accounts.len() as u32,
+                });
+            }
+
+            Ok(())
+        }
+
+        /// Burns `values[i]` of `ids[i]` from the caller as the inputs of crafting recipe `recipe_id`,
+        /// emitting `Redeemed` so the backend can grant the output. Either every input is burned or none is.
+        #[ink(message)]
+        pub fn redeem(&mut self, ids: Vec<TokenId>, values: Vec<TokenBalance>, recipe_id: u32) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if ids.len() != values.len() {
+                return Err(Error::InvalidArrayLength(ids.len() as u32, values.len() as u32));
+            }
+
+            if values.iter().any(|value| *value == 0) {
+                return Err(Error::ZeroValue);
+            }
+
+            let totals = Self::totals_by_id(&ids, &values);
+            for (id, value) in totals.iter() {
+                self.ensure_balance(&caller, id, *value)?;
+            }
+
+            for (id, value) in totals {
+                self.burn_token_from(&caller, &id, value)?;
+            }
+
+            self.env().emit_event(TransferBatch {
+                operator: caller,
+                from: caller,
+                to: AccountId::from(ZERO_ACCOUNT),
+                ids: ids.clone(),
+                values: values.clone(),
+            });
+
+            self.env().emit_event(Redeemed {
+                account: caller,
+                ids,
+                values,
+                recipe_id,
+            });
+
+            Ok(())
+        }
+
+        fn batch_transfer_from(&mut self, operator: AccountId, from: AccountId, to: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
+            if is_zero_account(&from) || is_zero_account(&to) {
+                return Err(Error::NotApproved);
+            }
+
+            if ids.len() != values.len() {
+                return Err(Error::InvalidArrayLength(ids.len() as u32, values.len() as u32));
+            }
+
+            if ids.is_empty() {
+                return Err(Error::EmptyBatch);
+            }
+
+            if ids.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge);
+            }
+
+            if values.iter().any(|value| *value == 0) {
+                return Err(Error::ZeroValue);
+            }
+
+            if !self.approved_or_owner(from, operator) {
+                return Err(Error::NotApproved);
+            }
+
+            let totals = Self::totals_by_id(&ids, &values);
+            for (id, value) in totals.iter() {
+                self.ensure_transferable(&from, &to, id, *value)?;
+            }
+
+            // Entries move one by one so the event can report what `to` received for each.
+            let mut received = Vec::with_capacity(values.len());
+            for (id, value) in ids.iter().zip(values.iter()) {
+                received.push(self.transfer_token_from(&from, &to, id, *value)?);
+            }
+
+            self.env().emit_event(TransferBatch {
+                operator,
+                from,
+                to,
+                ids,
+                values: received,
+            });
+
+            Ok(())
+        }
+
+        /// Performs every check and every balance and supply write of a transfer. Callers emit
+        /// their events only after this returns, and any call into the recipient (such as a
+        /// future receiver hook) must come after those, so a reentrant call always observes
+        /// the completed transfer (checks-effects-interactions). `wrap` and `unwrap` follow the
+        /// same order around their call to the wrapper, and undo their writes if it fails.
+        /// Returns the amount `to` received after the transfer fee, which callers report in
+        /// their events.
+        fn transfer_token_from(&mut self, from: &AccountId, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<TokenBalance, Error> {
+            self.ensure_transferable(from, to, id, value)?;
+
+            // A transfer to oneself only needs the checks; the caller still emits the event.
+            if from == to {
+                return Ok(value);
+            }
+
+            self.move_token(from, to, id, value)
+        }
+
+        /// Performs every check of a transfer without writing anything, so a caller moving
+        /// several legs can validate all of them before the first one is applied.
+        fn ensure_transferable(&self, from: &AccountId, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            if self.frozen_or_false(from) || self.frozen_or_false(to) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if self.paused_or_false(id) {
+                return Err(Error::TokenPaused);
+            }
+
+            let operator = self.env().caller();
+            if self.enforce_royalties && operator != *from && !self.is_operator_allowed(operator) {
+                return Err(Error::RoyaltyNotEnforced);
+            }
+
+            if !self.transfers_before_reveal && self.env().block_number() < self.reveal_block {
+                return Err(Error::TransfersLockedUntilReveal);
+            }
+
+            if *to == self.env().account_id() && !self.accept_contract_transfers {
+                return Err(Error::TransferToSelfContract);
+            }
+
+            self.ensure_unlocked(from, id, value)?;
+
+            if let (Some(cooldown), Some(last_received)) = (self.transfer_cooldown.get(id), self.last_received.get(&(*from, *id))) {
+                if self.env().block_timestamp().saturating_sub(*last_received) < *cooldown {
+                    return Err(Error::TransferCooldown);
+                }
+            }
+
+            self.transfer_fee_of(value)?;
+
+            self.ensure_balance(from, id, value)
+        }
+
+        /// Fails if ``account``'s tokens of type `id` may not be locked or unlocked right now.
+        fn ensure_lockable(&self, account: &AccountId, id: &TokenId) -> Result<(), Error> {
+            if self.frozen_or_false(account) {
+                return Err(Error::AccountFrozen);
+            }
+
+            if self.paused_or_false(id) {
+                return Err(Error::TokenPaused);
+            }
+
+            Ok(())
+        }
+
+        /// Fails if moving `value` of `id` out of ``from``'s balance would dip into its vesting lock.
+        fn ensure_unlocked(&self, from: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            let locked = self.vesting_locked_of(from, id);
+            if locked > 0 && self.balance_of_or_zero(from, id) < locked + value {
+                return Err(Error::TokensLocked);
+            }
+
+            Ok(())
+        }
+
+        /// Moves `value` of `id` from `from` to `to`, taking the transfer fee, and returns the
+        /// amount `to` received. Expects the transfer to have passed `ensure_transferable`.
+        fn move_token(&mut self, from: &AccountId, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<TokenBalance, Error> {
+            let fee = self.transfer_fee_of(value)?;
+            if fee > 0 {
+                let fee_to = match self.fee_recipient {
+                    Some(fee_recipient) => {
+                        self.remove_token_from(from, id, fee)?;
+                        self.add_token_to(&fee_recipient, id, fee)?;
+                        fee_recipient
+                    }
+                    None => {
+                        self.burn_token_from(from, id, fee)?;
+                        AccountId::from(ZERO_ACCOUNT)
+                    }
+                };
+
+                self.env().emit_event(TransferSingle {
+                    operator: self.env().caller(),
+                    from: *from,
+                    to: fee_to,
+                    id: *id,
+                    value: fee,
+                });
+            }
+
+            self.remove_token_from(from, id, value - fee)?;
+            self.add_token_to(to, id, value - fee)?;
+            self.record_receipt(to, id);
+            self.mark_active(from);
+            self.mark_active(to);
+
+            Ok(value - fee)
+        }
+
+        fn transfer_fee_of(&self, value: TokenBalance) -> Result<TokenBalance, Error> {
+            value.checked_mul(TokenBalance::from(self.transfer_fee_bps))
+                .map(|fee| fee / 10_000)
+                .ok_or(Error::FeeOverflow)
+        }
+
+        /// Sums the values of ids that appear more than once in a batch, keeping the order in
+        /// which each id first appears.
+        fn totals_by_id(ids: &[TokenId], values: &[TokenBalance]) -> Vec<(TokenId, TokenBalance)> {
+            let mut totals: Vec<(TokenId, TokenBalance)> = Vec::new();
+
+            for (id, value) in ids.iter().zip(values.iter()) {
+                match totals.iter_mut().find(|(total_id, _)| total_id == id) {
+                    Some((_, total)) => {
+                        *total = total.saturating_add(*value);
+                    }
+                    None => totals.push((*id, *value)),
+                }
+            }
+
+            totals
+        }
+
+        fn wrapper_for(&self, id: TokenId) -> Result<AccountId, Error> {
+            match self.wrapper {
+                Some((wrapper, wrapped_id)) if wrapped_id == id => Ok(wrapper),
+                _ => Err(Error::NoWrapper),
+            }
+        }
+
+        /// Calls `mint` or `burn` on the wrapper ERC20 with `(account, value)`.
+        fn call_wrapper(&self, wrapper: AccountId, selector: [u8; 4], account: AccountId, value: TokenBalance) -> Result<(), Error> {
+            <WrapperTransport as WrapperCaller>::call(self, wrapper, selector, account, value)
+        }
+
+        fn allocate_token(&mut self, creator: AccountId, uri: Vec<u8>) -> TokenId {
+            let id = self.next_token_id;
+            self.next_token_id = id + 1;
+
+            self.register_token(id, creator, uri);
+
+            id
+        }
+
+        fn register_token(&mut self, id: TokenId, creator: AccountId, uri: Vec<u8>) {
+            self.token_creator.insert(id, creator);
+            self.token_uri.insert(id, uri.clone());
+
+            self.env().emit_event(TokenCreated {
+                creator,
+                id,
+                uri,
+            });
+        }
+
+        /// Registers a caller-chosen `id` and moves `next_token_id` past it so `create` never
+        /// hands it out again.
+        fn reserve_token(&mut self, id: TokenId, creator: AccountId, uri: Vec<u8>) -> Result<(), Error> {
+            if id >= WRAPPED_NATIVE_ID {
+                return Err(Error::ReservedId);
+            }
+
+            self.register_token(id, creator, uri);
+            if id >= self.next_token_id {
+                self.next_token_id = id.checked_add(1).ok_or(Error::ReservedId)?;
+            }
+
+            Ok(())
+        }
+
+        fn is_nft_type(&self, id: TokenId) -> bool {
+            id & NF_BIT != 0 && self.index(id) == 0 && self.token_creator.contains_key(&id)
+        }
+
+        /// Mints the companion token for a mint of `id`. Minting the companion itself does not
+        /// mint another companion, so a companion id equal to `id` cannot recurse.
+        fn mint_companion_to(&mut self, operator: AccountId, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            let companion_id = match self.companion_id {
+                Some(companion_id) if companion_id != id => companion_id,
+                _ => return Ok(()),
+            };
+
+            self.mint_token_to(&to, &companion_id, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator,
+                from: AccountId::from(ZERO_ACCOUNT),
+                to,
+                id: companion_id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        fn mint_token_to(&mut self, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            self.ensure_mintable(to, id, value)?;
+            self.issue_token_to(to, id, value)
+        }
+
+        /// Performs every check of `mint_token_to` without writing anything, so a paid mint can
+        /// be validated before its fee is taken.
+        fn ensure_mintable(&self, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            if self.minting_finalized {
+                return Err(Error::MintingFinalized);
+            }
+
+            // Only `deposit` may create wrapped native tokens, or `withdraw` would pay out
+            // native balance that was never paid in.
+            if *id == WRAPPED_NATIVE_ID {
+                return Err(Error::ReservedId);
+            }
+
+            self.ensure_issuable(to, id, value)
+        }
+
+        /// The checks of `issue_token_to`.
+        fn ensure_issuable(&self, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            if self.paused_or_false(id) {
+                return Err(Error::TokenPaused);
+            }
+
+            if self.supply_locked_or_false(id) {
+                return Err(Error::SupplyLocked);
+            }
+
+            let supply = self.supply_of_or_zero(id);
+
+            // Each allocated instance of a non-fungible type exists at most once, and a burned
+            // instance stays burned. Wrapped instances are retired rather than burned, so they
+            // can still come back through `unwrap`.
+            if id & NF_BIT != 0 {
+                let max_index = *self.nft_max_index.get(&self.base_type(*id)).unwrap_or(&0);
+                if !self.is_nft(*id) || self.index(*id) > max_index || value != 1 || supply != 0 || self.total_burned(*id) > 0 {
+                    return Err(Error::InvalidNftMint);
+                }
+            }
+            if let Some(cap) = self.max_supply.get(id) {
+                if supply + value > *cap {
+                    return Err(Error::MaxSupplyExceeded);
+                }
+            }
+
+            if let Some(wallet_cap) = self.max_mint_per_wallet.get(id) {
+                if self.minted_by_wallet_of(*to, *id) + value > *wallet_cap {
+                    return Err(Error::WalletMintLimitExceeded);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Mints without the checks of `mint_token_to` that only apply to freshly created
+        /// tokens, for `deposit`, whose tokens are backed by the native value paid in.
+        fn issue_token_to(&mut self, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            self.ensure_issuable(to, id, value)?;
+
+            let supply = self.supply_of_or_zero(id);
+            let cap = self.max_supply.get(id).cloned();
+            let minted = self.minted_by_wallet_of(*to, *id);
+
+            self.add_token_to(to, id, value)?;
+            self.record_receipt(to, id);
+            self.mark_active(to);
+            self.minted_by_wallet.insert((*to, *id), minted + value);
+
+            if supply == 0 && value > 0 {
+                self.token_count += 1;
+            }
+            if value > 0 && self.total_supply.get(id).is_none() {
+                self.token_type_count += 1;
+            }
+            self.total_supply.insert(*id, supply + value);
+
+            if self.auto_lock_at_cap && cap == Some(supply + value) {
+                self.supply_locked.insert(*id, true);
+            }
+
+            Ok(())
+        }
+
+        fn burn_token_from(&mut self, from: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            self.retire_token_from(from, id, value)?;
+
+            let burned = self.total_burned(*id);
+            self.burned_totals.insert(*id, burned.saturating_add(value));
+            self.mark_active(from);
+
+            Ok(())
+        }
+
+        /// Takes `value` of `id` out of ``from``'s balance and the supply without counting it as
+        /// burned, for tokens that move to the wrapper and can come back.
+        fn retire_token_from(&mut self, from: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            self.ensure_balance(from, id, value)?;
+
+            let supply = self.supply_of_or_zero(id);
+            // A supply below a held balance means the counters are out of sync; refuse rather
+            // than wrap around.
+            let remaining = supply.checked_sub(value).ok_or(Error::SupplyUnderflow)?;
+
+            self.remove_token_from(from, id, value)?;
+
+            if supply > 0 && remaining == 0 {
+                self.token_count -= 1;
+            }
+            self.total_supply.insert(*id, remaining);
+
+            Ok(())
+        }
+
+        /// Puts `value` of `id` retired by `retire_token_from` back into ``to``'s balance and
+        /// the supply.
+        fn reissue_token_to(&mut self, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            let supply = self.supply_of_or_zero(id);
+            let new_supply = supply.checked_add(value).ok_or(Error::SupplyOverflow)?;
+
+            self.add_token_to(to, id, value)?;
+
+            if supply == 0 && value > 0 {
+                self.token_count += 1;
+            }
+            self.total_supply.insert(*id, new_supply);
+
+            Ok(())
+        }
+
+        fn add_token_to(&mut self, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            let to_balance = self.balance_of_or_zero(&to, &id);
+            // Locked tokens still count as held, so an account with some locked is already indexed.
+            if to_balance == 0 && value > 0 && self.locked_balance_of(*to, *id) == 0 {
+                let tokens = self.owned_tokens.entry(*to).or_insert_with(Vec::new);
+                // An account becomes a holder with its first nonzero balance in any id.
+                if tokens.is_empty() {
+                    self.holder_count += 1;
+                }
+                tokens.push(*id);
+                self.holder_index.entry(*id).or_insert_with(Vec::new).push(*to);
+            }
+
+            self.write_checkpoint(to, id);
+            self.balances.insert(Self::key_of(BALANCES_PREFIX, &(*to, *id)), to_balance + value);
+
+            Ok(())
+        }
+
+        /// Takes the mint fee for `value` tokens out of the value sent with the call, forwarding
+        /// it to the fee recipient, or the treasury when none is set, and refunds the rest to
+        /// `payer`. Without a mint fee the whole payment is refunded.
+        fn settle_mint_fee(&mut self, payer: AccountId, value: TokenBalance) -> Result<(), Error> {
+            let fee = self.mint_fee.checked_mul(value).ok_or(Error::InsufficientFee)?;
+            let payment = self.env().transferred_balance();
+            if payment < fee {
+                return Err(Error::InsufficientFee);
+            }
+
+            if fee > 0 {
+                let fee_to = self.fee_recipient.unwrap_or(self.treasury);
+                self.env().transfer(fee_to, fee).map_err(|_| Error::NativeTransferFailed)?;
+            }
+
+            let excess = payment - fee;
+            if excess > 0 {
+                self.env().transfer(payer, excess).map_err(|_| Error::NativeTransferFailed)?;
+            }
+
+            Ok(())
+        }
+
+        fn mark_active(&mut self, account: &AccountId) {
+            let now = self.env().block_timestamp();
+            self.last_active.insert(*account, now);
+        }
+
+        /// Remembers when `account` last received `id`, for tokens with a transfer cooldown.
+        fn record_receipt(&mut self, account: &AccountId, id: &TokenId) {
+            if self.transfer_cooldown.contains_key(id) {
+                let now = self.env().block_timestamp();
+                self.last_received.insert((*account, *id), now);
+            }
+        }
+
+        fn remove_token_from(&mut self, from: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
+            let from_balance = self.balance_of_or_zero(from, id);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance { id: *id, available: from_balance, required: value });
+            }
+
+            if from_balance == value && value > 0 && self.locked_balance_of(*from, *id) == 0 {
+                if let Some(tokens) = self.owned_tokens.get_mut(from) {
+                    tokens.retain(|token| token != id);
+                    if tokens.is_empty() {
+                        self.holder_count -= 1;
+                    }
+                }
+                if let Some(holders) = self.holder_index.get_mut(id) {
+                    holders.retain(|holder| holder != from);
+                }
+            }
+
+            self.write_checkpoint(from, id);
+            self.balances.insert(Self::key_of(BALANCES_PREFIX, &(*from, *id)), from_balance - value);
+
+            Ok(())
+        }
+
+        /// Records ``account``'s balance of `id` for the latest snapshot before it first changes
+        /// after that snapshot.
+        fn write_checkpoint(&mut self, account: &AccountId, id: &TokenId) {
+            if self.snapshot_id == 0 {
+                return;
+            }
+
+            let balance = self.balance_of_or_zero(account, id);
+            let snapshot_id = self.snapshot_id;
+            let checkpoints = self.balance_checkpoints.entry((*account, *id)).or_insert_with(Vec::new);
+            if checkpoints.last().map(|(checkpoint_id, _)| *checkpoint_id) != Some(snapshot_id) {
+                checkpoints.push((snapshot_id, balance));
+            }
+        }
+
+        /// Every approval entry point goes through this check so none of them can make
+        /// an account its own operator.
+        fn ensure_not_self_approval(&self, account: &AccountId, operator: &AccountId) -> Result<(), Error> {
+            if account == operator {
+                return Err(Error::ApprovalForSelf);
+            }
+
+            Ok(())
+        }
+
+        fn ensure_operator_allowed(&self, operator: &AccountId, approved: bool) -> Result<(), Error> {
+            if approved && self.approvals_paused {
+                return Err(Error::ApprovalsPaused);
+            }
+
+            if approved && self.enforce_allowlist && !self.is_operator_allowed(*operator) {
+                return Err(Error::OperatorNotAllowed);
+            }
+
+            Ok(())
+        }
+
+        /// Fails if approving `operators` would leave `account` with more than
+        /// `MAX_OPERATORS_PER_ACCOUNT` operators. Operators it already approved do not count twice.
+        fn ensure_operator_capacity(&self, account: &AccountId, operators: &[AccountId], approved: bool) -> Result<(), Error> {
+            if !approved {
+                return Ok(());
+            }
+
+            let mut approved_operators = self.approved_operators.get(account).cloned().unwrap_or_default();
+            for operator in operators {
+                if !approved_operators.contains(operator) {
+                    approved_operators.push(*operator);
+                }
+            }
+
+            if approved_operators.len() > MAX_OPERATORS_PER_ACCOUNT {
+                return Err(Error::TooManyOperators);
+            }
+
+            Ok(())
+        }
+
+        fn approved_or_owner(&self, account: AccountId, caller: AccountId) -> bool {
+            !is_zero_account(&account)
+                && (account == caller || self.approved_for_all(&account, &caller))
+        }
+
+        fn set_owner(&mut self, new_owner: AccountId) {
+            let previous_owner = self.owner;
+            self.owner = new_owner;
+            self.pending_owner = None;
+
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            });
+        }
+
+        fn is_owner(&self, account: AccountId) -> bool {
+            account == self.owner
+        }
+
+        /// Only registered ids can be minted, so an authorized minter cannot put supply into an id
+        /// `create` will later hand to someone else.
+        fn is_minter(&self, caller: AccountId, id: TokenId) -> bool {
+            self.is_registered(id) && (self.is_creator(caller, id) || self.is_authorized_minter(caller))
+        }
+
+        /// True once `id`, or the non-fungible type of an instance `id`, has a creator, so
+        /// `create` can no longer hand it out.
+        fn is_registered(&self, id: TokenId) -> bool {
+            let id = if self.is_nft(id) { self.base_type(id) } else { id };
+            self.token_creator.contains_key(&id)
+        }
+
+        /// Instances of a non-fungible type belong to the creator of the type.
+        fn is_creator(&self, caller: AccountId, id: TokenId) -> bool {
+            let id = if self.is_nft(id) { self.base_type(id) } else { id };
+            self.token_creator.get(&id) == Some(&caller)
+        }
+
+        fn ensure_balance(&self, account: &AccountId, id: &TokenId, required: TokenBalance) -> Result<(), Error> {
+            let available = self.balance_of_or_zero(account, id);
+            if available < required {
+                return Err(Error::InsufficientBalance { id: *id, available, required });
+            }
+
+            Ok(())
+        }
+
+        fn balance_of_or_zero(&self, account: &AccountId, id: &TokenId) -> TokenBalance {
+            *self.balances.get(&Self::key_of(BALANCES_PREFIX, &(*account, *id))).unwrap_or(&0)
+        }
+
+        /// Derives the storage key of a composite `key` in the map identified by `prefix`, as the
+        /// Blake2x256 hash of both SCALE-encoded.
+        fn key_of<K: Encode>(prefix: &[u8], key: &K) -> Hash {
+            Hash::from(Self::env().hash_encoded::<Blake2x256, _>(&(prefix, key)))
+        }
+
+        fn vesting_locked_of(&self, account: &AccountId, id: &TokenId) -> TokenBalance {
+            match self.locks.get(&(*account, *id)) {
+                Some((locked, unlock_at)) if self.env().block_timestamp() < *unlock_at => *locked,
+                _ => 0,
+            }
+        }
+
+        fn supply_of_or_zero(&self, id: &TokenId) -> TokenBalance {
+            *self.total_supply.get(id).unwrap_or(&0)
+        }
+
+        fn supply_locked_or_false(&self, id: &TokenId) -> bool {
+            *self.supply_locked.get(id).unwrap_or(&false)
+        }
+
+        fn allowance_of_or_zero(&self, owner: &AccountId, operator: &AccountId, id: Option<TokenId>) -> TokenBalance {
+            if self.approved_for_all(owner, operator) {
+                return TokenBalance::MAX;
+            }
+
+            match id {
+                Some(id) => *self.allowances.get(&(*owner, *operator, id)).unwrap_or(&0),
+                None => 0,
+            }
+        }
+
+        fn set_operator_approval(&mut self, account: AccountId, operator: AccountId, approved: bool, expires_at: Option<Timestamp>) {
+            self.operator_approvals.insert(Self::key_of(OPERATOR_APPROVALS_PREFIX, &(account, operator)), (approved, expires_at));
+
+            if approved {
+                let operators = self.approved_operators.entry(account).or_insert_with(Vec::new);
+                if !operators.contains(&operator) {
+                    operators.push(operator);
+                }
+            } else if let Some(operators) = self.approved_operators.get_mut(&account) {
+                operators.retain(|approved_operator| *approved_operator != operator);
+            }
+
+            if self.is_default_operator(operator) {
+                if approved {
+                    self.revoked_default_operators.take(&(account, operator));
+                } else {
+                    self.revoked_default_operators.insert((account, operator), true);
+                }
+            }
+
+            self.env().emit_event(ApprovalForAll {
+                account,
+                operator,
+                approved,
+            });
+        }
+
+        fn approved_for_all(&self, account: &AccountId, operator: &AccountId) -> bool {
+            let explicitly_approved = match self.operator_approvals.get(&Self::key_of(OPERATOR_APPROVALS_PREFIX, &(*account, *operator))) {
+                Some((approved, Some(expires_at))) => *approved && self.env().block_timestamp() <= *expires_at,
+                Some((approved, None)) => *approved,
+                None => false,
+            };
+
+            explicitly_approved
+                || (self.is_default_operator(*operator)
+                    && !*self.revoked_default_operators.get(&(*account, *operator)).unwrap_or(&false))
+        }
+
+        fn expand_base_uri(&self, id: TokenId) -> Vec<u8> {
+            const PLACEHOLDER: &[u8] = b"{id}";
+            const HEX_DIGITS: &[u8] = b"0123456789abcdef";
+
+            let mut hex_id = [b'0'; 64];
+            let mut remaining = id;
+            let mut position = hex_id.len();
+            while remaining > 0 {
+                position -= 1;
+                hex_id[position] = HEX_DIGITS[(remaining & 0xf) as usize];
+                remaining >>= 4;
+            }
+
+            let template = &self.base_uri;
+            let mut uri = Vec::with_capacity(template.len());
+            let mut i = 0;
+            while i < template.len() {
+                if template[i..].starts_with(PLACEHOLDER) {
+                    uri.extend_from_slice(&hex_id);
+                    i += PLACEHOLDER.len();
+                } else {
+                    uri.push(template[i]);
+                    i += 1;
+                }
+            }
+
+            uri
+        }
+
+        fn nonce_or_zero(&self, account: &AccountId) -> u128 {
+            *self.nonces.get(account).unwrap_or(&0)
+        }
+
+        fn frozen_or_false(&self, account: &AccountId) -> bool {
+            *self.frozen.get(account).unwrap_or(&false)
+        }
+
+        fn paused_or_false(&self, id: &TokenId) -> bool {
+            *self.paused_ids.get(id).unwrap_or(&false)
+        }
+
+        /// Returns true if token `id` exists or false if it does not.
+        fn exists(&self, id: TokenId) -> bool {
+            self.token_creator.get(&id).is_some() && self.token_creator.contains_key(&id)
+        }
+
+    }
+
+    impl MultiToken for Subgame1 {
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId, id: TokenId) -> TokenBalance {
+            self.balance_of_or_zero(&owner, &id)
+        }
+
+        #[ink(message)]
+        fn safe_transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            Subgame1::safe_transfer_from(self, from, to, id, value)
+        }
+
+        #[ink(message)]
+        fn mint(&mut self, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            Subgame1::mint(self, to, id, value).map(|_| ())
+        }
+
+        #[ink(message)]
+        fn burn(&mut self, from: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+            Subgame1::burn(self, from, id, value).map(|_| ())
+        }
+    }
+
+    impl PSP37 for Subgame1 {
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId, id: Option<TokenId>) -> TokenBalance {
+            match id {
+                Some(id) => self.balance_of_or_zero(&owner, &id),
+                None => self.owned_tokens.get(&owner).map_or(0, |tokens| tokens.len() as TokenBalance),
+            }
+        }
+
+        #[ink(message)]
+        fn total_supply(&self, id: Option<TokenId>) -> TokenBalance {
+            match id {
+                Some(id) => self.supply_of_or_zero(&id),
+                None => TokenBalance::from(self.token_count),
+            }
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<TokenId>) -> TokenBalance {
+            self.allowance_of_or_zero(&owner, &operator, id)
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, operator: AccountId, id: Option<TokenId>, value: TokenBalance) -> Result<(), PSP37Error> {
+            let caller = self.env().caller();
+
+            self.ensure_not_self_approval(&caller, &operator)?;
+
+            match id {
+                Some(id) => {
+                    self.ensure_operator_allowed(&operator, value > 0)?;
+                    self.allowances.insert((caller, operator, id), value);
+                }
+                None => {
+                    self.ensure_operator_allowed(&operator, value > 0)?;
+                    self.ensure_operator_capacity(&caller, &[operator], value > 0)?;
+                    self.set_operator_approval(caller, operator, value > 0, None);
+                }
+            }
+
+            self.env().emit_event(Approval {
+                owner: caller,
+                operator,
+                id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, id: TokenId, value: TokenBalance, _data: Vec<u8>) -> Result<(), PSP37Error> {
+            let caller = self.env().caller();
+
+            if is_zero_account(&to) {
+                return Err(PSP37Error::TransferToZeroAddress);
+            }
+
+            let received = self.transfer_token_from(&caller, &to, &id, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from: caller,
+                to,
+                id,
+                value: received,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId, value: TokenBalance, _data: Vec<u8>) -> Result<(), PSP37Error> {
+            let caller = self.env().caller();
+
+            if is_zero_account(&to) {
+                return Err(PSP37Error::TransferToZeroAddress);
+            }
+
+            let received = if self.approved_or_owner(from, caller) {
+                self.transfer_token_from(&from, &to, &id, value)?
+            } else {
+                let allowance = self.allowance_of_or_zero(&from, &caller, Some(id));
+                if allowance < value {
+                    return Err(PSP37Error::NotAllowed);
+                }
+
+                let received = self.transfer_token_from(&from, &to, &id, value)?;
+                self.allowances.insert((from, caller, id), allowance - value);
+                received
+            };
+
+            self.env().emit_event(TransferSingle {
+                operator: caller,
+                from,
+                to,
+                id,
+                value: received,
+            });
+
+            Ok(())
+        }
+    }
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+        use scale::Decode;
+
+        /// A game contract that only knows the token through `MultiToken`, the way a consumer
+        /// holding the generated reference type would. The off-chain environment cannot
+        /// dispatch calls between contracts, so it is handed the token directly.
+        struct Arena<T: MultiToken> {
+            token: T,
+            prize_pool: AccountId,
+        }
+
+        impl<T: MultiToken> Arena<T> {
+            fn award(&mut self, winner: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
+                self.token.safe_transfer_from(self.prize_pool, winner, id, value)
+            }
+        }
+
+        /// Stands in for the wrapper ERC20 contract, recording every call it receives and the
+        /// token's state as a contract calling back into it would see it during that call.
+        pub(super) mod mock_erc20 {
+            use super::*;
+            use std::cell::RefCell;
+            use std::collections::HashMap;
+
+            thread_local! {
+                static CALLS: RefCell<Vec<(AccountId, [u8; 4], AccountId, TokenBalance)>> = RefCell::new(Vec::new());
+                static BALANCES: RefCell<HashMap<AccountId, TokenBalance>> = RefCell::new(HashMap::new());
+                static OBSERVED: RefCell<Vec<(TokenBalance, TokenBalance)>> = RefCell::new(Vec::new());
+            }
+
+            pub enum MockWrapperCaller {}
+
+            impl WrapperCaller for MockWrapperCaller {
+                fn call(token: &Subgame1, wrapper: AccountId, selector: [u8; 4], account: AccountId, value: TokenBalance) -> Result<(), Error> {
+                    record_call(token, wrapper, selector, account, value)
+                }
+            }
+
+            fn record_call(token: &Subgame1, wrapper: AccountId, selector: [u8; 4], account: AccountId, value: TokenBalance) -> Result<(), Error> {
+                CALLS.with(|calls| calls.borrow_mut().push((wrapper, selector, account, value)));
+
+                if let Some((_, id)) = token.wrapper() {
+                    let observed = (token.balance_of(account, id), PSP37::total_supply(token, Some(id)));
+                    OBSERVED.with(|calls| calls.borrow_mut().push(observed));
+                }
+
+                BALANCES.with(|balances| {
+                    let mut balances = balances.borrow_mut();
+                    let balance = *balances.get(&account).unwrap_or(&0);
+                    match selector {
+                        WRAPPER_MINT_SELECTOR => {
+                            balances.insert(account, balance + value);
+                            Ok(())
+                        }
+                        WRAPPER_BURN_SELECTOR if balance >= value => {
+                            balances.insert(account, balance - value);
+                            Ok(())
+                        }
+                        _ => Err(Error::WrapperCallFailed),
+                    }
+                })
+            }
+
+            pub fn calls() -> Vec<(AccountId, [u8; 4], AccountId, TokenBalance)> {
+                CALLS.with(|calls| calls.borrow().clone())
+            }
+
+            pub fn balance_of(account: AccountId) -> TokenBalance {
+                BALANCES.with(|balances| *balances.borrow().get(&account).unwrap_or(&0))
+            }
+
+            /// The caller's balance and the total supply of the wrapped id during each call.
+            pub fn observed() -> Vec<(TokenBalance, TokenBalance)> {
+                OBSERVED.with(|calls| calls.borrow().clone())
+            }
+        }
+
+        /// Account of the ed25519 key used to sign the test permits and vouchers, which is its public key.
+        const SIGNER: [u8; 32] = [
+            0x19, 0x7f, 0x6b, 0x23, 0xe1, 0x6c, 0x85, 0x32, 0xc6, 0xab, 0xc8, 0x38, 0xfa, 0xcd, 0x5e, 0xa7,
+            0x89, 0xbe, 0x0c, 0x76, 0xb2, 0x92, 0x03, 0x34, 0x03, 0x9b, 0xfa, 0x8b, 0x3d, 0x36, 0x8d, 0x61,
+        ];
+
+        /// `SIGNER`'s signature approving `[0x02; 32]` with nonce 0 and no deadline.
+        const PERMIT_SIGNATURE: [u8; 64] = [
+            0x93, 0x30, 0x50, 0x65, 0x71, 0x37, 0x3e, 0x7d, 0xd0, 0x8d, 0x4d, 0xdb, 0xd4, 0x67, 0x98, 0x66,
+            0xe8, 0x0b, 0x85, 0x42, 0x36, 0x42, 0x6d, 0x74, 0x6c, 0x58, 0x2f, 0xa6, 0xbf, 0xfb, 0xbf, 0x17,
+            0x8d, 0x8d, 0x62, 0x59, 0x99, 0x49, 0x87, 0x4f, 0xb4, 0xa9, 0xcd, 0x64, 0x55, 0x18, 0x6d, 0x25,
+            0x72, 0x52, 0x3a, 0x00, 0xbc, 0xaf, 0xe6, 0x3a, 0xd9, 0x60, 0xba, 0x49, 0x6a, 0x04, 0x33, 0x0b,
+        ];
+
+        /// `SIGNER`'s signature approving `[0x02; 32]` with nonce 0 and a deadline of 0.
+        const EXPIRED_PERMIT_SIGNATURE: [u8; 64] = [
+            0x9c, 0x5e, 0xa3, 0xe8, 0x0e, 0x81, 0xc2, 0x36, 0x1f, 0xd1, 0xe8, 0x68, 0x3d, 0x14, 0x6d, 0xce,
+            0xcf, 0xcb, 0x29, 0xdd, 0x4c, 0x0c, 0xcf, 0x0d, 0xda, 0x0f, 0xf1, 0xa6, 0x5f, 0x1a, 0x0a, 0xe1,
+            0x14, 0x69, 0x8e, 0x26, 0x62, 0x89, 0xb9, 0x33, 0xe9, 0x8a, 0x8e, 0x3f, 0x30, 0xba, 0xd2, 0x74,
+            0x36, 0x39, 0x01, 0x20, 0x35, 0x9f, 0xa6, 0x3b, 0x61, 0x00, 0x01, 0xc7, 0x1e, 0x01, 0xee, 0x06,
+        ];
+
+        /// `SIGNER`'s signature of the `PERMIT_SIGNATURE` approval for a contract at `[0x08; 32]`.
+        const OTHER_CONTRACT_PERMIT_SIGNATURE: [u8; 64] = [
+            0x65, 0x2f, 0x37, 0xe4, 0xa7, 0x20, 0x43, 0xad, 0x80, 0xdd, 0xc6, 0xe1, 0x6b, 0x51, 0xe0, 0xef,
+            0x7b, 0x56, 0x2b, 0x14, 0x82, 0x20, 0x06, 0xe1, 0x73, 0x64, 0x22, 0xfc, 0x91, 0x21, 0x1b, 0xc9,
+            0xff, 0x86, 0xc9, 0xde, 0xfb, 0x7e, 0xb0, 0x79, 0x26, 0x37, 0xba, 0x1e, 0x78, 0xf9, 0xed, 0xd0,
+            0x49, 0x9a, 0x38, 0xdc, 0xdf, 0x9e, 0x9f, 0x93, 0x2e, 0xd6, 0xc3, 0xca, 0x42, 0x0b, 0x9a, 0x04,
+        ];
+
+        /// `SIGNER`'s voucher minting 5 of id 1 to `[0x02; 32]` for a price of 100 with nonce 0.
+        const VOUCHER_SIGNATURE: [u8; 64] = [
+            0x14, 0x23, 0x25, 0x96, 0x0e, 0x43, 0xe6, 0x66, 0xe8, 0xd5, 0xff, 0x28, 0x87, 0x95, 0x71, 0x67,
+            0x11, 0xca, 0xa2, 0x30, 0x7e, 0x4a, 0x07, 0x0e, 0x64, 0xf7, 0xa7, 0x3c, 0x42, 0x91, 0xe8, 0x8d,
+            0xbd, 0xd7, 0x3d, 0x17, 0x0a, 0xa3, 0x27, 0xd4, 0x1b, 0x88, 0x65, 0xe7, 0x4d, 0x90, 0xeb, 0xe8,
+            0x9e, 0x7d, 0xbe, 0xd2, 0xc2, 0x34, 0xcd, 0x05, 0xe9, 0xdb, 0x14, 0xe4, 0x05, 0xff, 0x6c, 0x06,
+        ];
+
+        /// `SIGNER`'s signature of the `VOUCHER_SIGNATURE` voucher for a contract at `[0x08; 32]`.
+        const OTHER_CONTRACT_VOUCHER_SIGNATURE: [u8; 64] = [
+            0x3b, 0x7f, 0x7d, 0xaa, 0x92, 0xb7, 0xc3, 0x61, 0xcb, 0x68, 0x1c, 0x77, 0x99, 0xe5, 0x30, 0x70,
+            0x6c, 0x15, 0xa5, 0xa0, 0x8e, 0x54, 0x5e, 0x25, 0x76, 0x0f, 0x43, 0xe9, 0x74, 0x47, 0xab, 0xb2,
+            0x7d, 0xd2, 0xb5, 0xdc, 0x7d, 0xa9, 0x59, 0x46, 0xd2, 0xb9, 0xb4, 0x8f, 0xb5, 0x3f, 0xbe, 0x1b,
+            0x3f, 0x31, 0xb3, 0x5d, 0xb7, 0xcc, 0xfd, 0x63, 0x85, 0xd9, 0xb5, 0x28, 0xe3, 0x0e, 0xd5, 0x0c,
+        ];
+
+        fn set_sender(sender: AccountId) {
+            set_sender_with_value(sender, 0);
+        }
+
+        fn set_sender_with_value(sender: AccountId, value: Balance) {
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            test::push_execution_context::<ink_env::DefaultEnvironment>(
+                sender,
+                callee,
+                1000000,
+                value,
+                test::CallData::new(call::Selector::new([0x00; 4])), // dummy
+            );
+        }
+
+        fn contract_id() -> AccountId {
+            ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get contract id")
+        }
+
+        fn native_balance_of(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<ink_env::DefaultEnvironment>(account)
+                .expect("Cannot get account balance")
+        }
+
+        #[ink::test]
+        fn create_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.creator_of(1), None);
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.creator_of(1), Some(accounts.alice));
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 0);
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
+        }
+
+        #[ink::test]
+        fn mint_batch_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([0, 0].to_vec()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([1000, 1000].to_vec()));
+        }
+
+        #[ink::test]
+        fn burn_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
+            assert_eq!(subgame1.burn(accounts.alice, 1, 200), Ok(800));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 800);
+        }
+
+        #[ink::test]
+        fn burn_batch_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([1000, 1000].to_vec()));
+            assert_eq!(subgame1.burn_batch(accounts.alice, [1, 2].to_vec(), [200, 200].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([800, 800].to_vec()));
+        }
+
+        #[ink::test]
+        fn safe_transfer_from_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 800);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 200);
+        }
+
+        #[ink::test]
+        fn safe_batch_transfer_from_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([1000, 1000].to_vec()));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [1, 2].to_vec(), [200, 200].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice, accounts.bob, accounts.bob].to_vec(), [1, 2, 1, 2].to_vec()), Ok([800, 800, 200, 200].to_vec()));
+        }
+
+        #[ink::test]
+        fn set_approval_for_all_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), true);
+        }
+
+        #[ink::test]
+        fn frozen_sender_cannot_transfer() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.freeze(accounts.alice), Ok(()));
+            assert_eq!(subgame1.is_frozen(accounts.alice), true);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Err(Error::AccountFrozen));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
+        }
+
+        #[ink::test]
+        fn frozen_recipient_cannot_receive() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.freeze(accounts.bob), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Err(Error::AccountFrozen));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Err(Error::AccountFrozen));
+            assert_eq!(subgame1.mint_batch(accounts.bob, [1].to_vec(), [1000].to_vec()), Err(Error::AccountFrozen));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+        }
+
+        #[ink::test]
+        fn transfer_works_after_unfreeze() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.freeze(accounts.bob), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Err(Error::AccountFrozen));
+            assert_eq!(subgame1.unfreeze(accounts.bob), Ok(()));
+            assert_eq!(subgame1.is_frozen(accounts.bob), false);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 800);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 200);
+        }
+
+        #[ink::test]
+        fn authorized_game_contract_can_mint() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_authorized_minter(accounts.django, true), Ok(()));
+            assert_eq!(subgame1.is_authorized_minter(accounts.django), true);
+
+            // The game logic contract mints loot for a player.
+            set_sender(accounts.django);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+            assert_eq!(subgame1.mint_batch(accounts.bob, [1].to_vec(), [5].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 15);
+
+            // Ids nobody created stay out of reach, so `create` never hands out an id with supply.
+            assert_eq!(subgame1.mint(accounts.bob, 2, 10), Err(Error::OnlyCreator));
+            assert_eq!(subgame1.mint_batch(accounts.bob, [1, 2].to_vec(), [5, 5].to_vec()), Err(Error::OnlyCreator));
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 0);
+        }
+
+        #[ink::test]
+        fn unauthorized_game_contract_cannot_mint() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_authorized_minter(accounts.django, true), Ok(()));
+            assert_eq!(subgame1.set_authorized_minter(accounts.django, false), Ok(()));
+
+            set_sender(accounts.django);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Err(Error::OnlyCreator));
+
+            set_sender(accounts.eve);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Err(Error::OnlyCreator));
+            assert_eq!(subgame1.set_authorized_minter(accounts.eve, true), Err(Error::OnlyOwner));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+        }
+
+        #[ink::test]
+        fn balance_attestation_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            let block_number = ink_env::block_number::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block number");
+            assert_eq!(subgame1.balance_attestation(accounts.alice, 1), (subgame1.balance_of(accounts.alice, 1), block_number));
+            assert_eq!(subgame1.balance_attestation(accounts.bob, 1), (0, block_number));
+        }
+
+        #[ink::test]
+        fn psp37_balance_and_supply_match_erc1155() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 500), Ok(500));
+            assert_eq!(subgame1.mint(accounts.alice, 2, 10), Ok(10));
+            assert_eq!(PSP37::balance_of(&subgame1, accounts.alice, Some(1)), subgame1.balance_of(accounts.alice, 1));
+            assert_eq!(PSP37::balance_of(&subgame1, accounts.bob, Some(1)), subgame1.balance_of(accounts.bob, 1));
+            assert_eq!(PSP37::balance_of(&subgame1, accounts.alice, None), 2);
+            assert_eq!(PSP37::balance_of(&subgame1, accounts.bob, None), 1);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 1500);
+            assert_eq!(PSP37::total_supply(&subgame1, None), 2);
+            assert_eq!(subgame1.burn(accounts.alice, 2, 10), Ok(0));
+            assert_eq!(PSP37::balance_of(&subgame1, accounts.alice, None), 1);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(2)), 0);
+            assert_eq!(PSP37::total_supply(&subgame1, None), 1);
+        }
+
+        #[ink::test]
+        fn psp37_transfer_matches_erc1155() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(PSP37::transfer(&mut subgame1, accounts.bob, 1, 200, Vec::new()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 800);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 200);
+            assert_eq!(PSP37::transfer(&mut subgame1, accounts.bob, 1, 1000, Vec::new()), Err(PSP37Error::InsufficientBalance));
+            assert_eq!(PSP37::transfer(&mut subgame1, AccountId::from([0x0; 32]), 1, 1, Vec::new()), Err(PSP37Error::TransferToZeroAddress));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 1000);
+        }
+
+        #[ink::test]
+        fn psp37_approve_and_transfer_from_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.alice, Some(1), 300), Err(PSP37Error::SelfApprove));
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.bob, Some(1), 300), Ok(()));
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.bob, Some(1)), 300);
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+
+            set_sender(accounts.bob);
+            assert_eq!(PSP37::transfer_from(&mut subgame1, accounts.alice, accounts.charlie, 1, 200, Vec::new()), Ok(()));
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.bob, Some(1)), 100);
+            assert_eq!(PSP37::transfer_from(&mut subgame1, accounts.alice, accounts.charlie, 1, 200, Vec::new()), Err(PSP37Error::NotAllowed));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 800);
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 200);
+        }
+
+        #[ink::test]
+        fn psp37_approve_all_matches_erc1155() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.bob, None, 1), Ok(()));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), true);
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.bob, Some(1)), TokenBalance::MAX);
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.bob, None, 0), Ok(()));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.bob, None), 0);
+        }
+
+        #[ink::test]
+        fn transfers_before_reveal_are_locked_when_disallowed() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let block_number = ink_env::block_number::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block number");
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_reveal_block(block_number + 2), Ok(()));
+            assert_eq!(subgame1.set_transfers_before_reveal(false), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Err(Error::TransfersLockedUntilReveal));
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 200);
+        }
+
+        #[ink::test]
+        fn transfers_before_reveal_work_when_allowed() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let block_number = ink_env::block_number::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block number");
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_reveal_block(block_number + 2), Ok(()));
+            assert_eq!(subgame1.transfers_before_reveal(), true);
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 400);
+        }
+
+        #[ink::test]
+        fn max_supply_is_enforced() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.max_supply_of(1), None);
+            assert_eq!(subgame1.set_max_supply(1, 100), Ok(()));
+            assert_eq!(subgame1.max_supply_of(1), Some(100));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 60), Ok(60));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 41), Err(Error::MaxSupplyExceeded));
+            assert_eq!(subgame1.set_max_supply(1, 50), Err(Error::MaxSupplyExceeded));
+            assert_eq!(subgame1.lock_supply(1), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1), Err(Error::SupplyLocked));
+        }
+
+        #[ink::test]
+        fn auto_lock_at_cap_blocks_remint_after_burn() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_auto_lock_at_cap(true), Ok(()));
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_max_supply(1, 100), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.is_supply_locked(1), true);
+            assert_eq!(subgame1.burn(accounts.alice, 1, 10), Ok(90));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Err(Error::SupplyLocked));
+        }
+
+        #[ink::test]
+        fn remint_after_burn_works_without_auto_lock() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_max_supply(1, 100), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.is_supply_locked(1), false);
+            assert_eq!(subgame1.burn(accounts.alice, 1, 10), Ok(90));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(100));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 100);
+        }
+
+        #[ink::test]
+        fn tokens_of_tracks_new_ids() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.tokens_of(accounts.alice), Vec::<TokenId>::new());
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [2, 2].to_vec(), [10, 10].to_vec()), Ok(()));
+            assert_eq!(subgame1.tokens_of(accounts.alice), [1, 2].to_vec());
+        }
+
+        #[ink::test]
+        fn tokens_of_ignores_existing_ids() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1400));
+            assert_eq!(subgame1.tokens_of(accounts.alice), [1].to_vec());
+            assert_eq!(subgame1.tokens_of(accounts.bob), [1].to_vec());
+        }
+
+        #[ink::test]
+        fn tokens_of_drops_fully_transferred_ids() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 1000), Ok(()));
+            assert_eq!(subgame1.tokens_of(accounts.alice), [2].to_vec());
+            assert_eq!(subgame1.tokens_of(accounts.bob), [1].to_vec());
+            assert_eq!(subgame1.burn(accounts.alice, 2, 1000), Ok(0));
+            assert_eq!(subgame1.tokens_of(accounts.alice), Vec::<TokenId>::new());
+        }
+
+        #[ink::test]
+        fn permit_works() {
+            let mut subgame1 = Subgame1::new();
+            let owner = AccountId::from(SIGNER);
+            let operator = AccountId::from([0x02; 32]);
+            assert_eq!(subgame1.permit(owner, operator, false, 0, Timestamp::MAX, PERMIT_SIGNATURE), Err(Error::InvalidSignature));
+            assert_eq!(subgame1.permit(owner, operator, true, 1, Timestamp::MAX, PERMIT_SIGNATURE), Err(Error::InvalidNonce));
+            assert_eq!(subgame1.permit(owner, operator, true, 0, Timestamp::MAX, PERMIT_SIGNATURE), Ok(()));
+            assert_eq!(subgame1.is_approved_for_all(owner, operator), true);
+        }
+
+        #[ink::test]
+        fn permit_replay_fails() {
+            let mut subgame1 = Subgame1::new();
+            let owner = AccountId::from(SIGNER);
+            let operator = AccountId::from([0x02; 32]);
+            assert_eq!(subgame1.permit(owner, operator, true, 0, Timestamp::MAX, PERMIT_SIGNATURE), Ok(()));
+            assert_eq!(subgame1.permit(owner, operator, true, 0, Timestamp::MAX, PERMIT_SIGNATURE), Err(Error::NonceAlreadyUsed));
+        }
+
+        #[ink::test]
+        fn expired_permit_fails() {
+            let mut subgame1 = Subgame1::new();
+            let owner = AccountId::from(SIGNER);
+            let operator = AccountId::from([0x02; 32]);
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(subgame1.permit(owner, operator, true, 0, 0, EXPIRED_PERMIT_SIGNATURE), Err(Error::PermitExpired));
+            assert_eq!(subgame1.is_approved_for_all(owner, operator), false);
+        }
+
+        #[ink::test]
+        fn permit_for_other_contract_fails() {
+            let mut subgame1 = Subgame1::new();
+            let owner = AccountId::from(SIGNER);
+            let operator = AccountId::from([0x02; 32]);
+            assert_eq!(subgame1.permit(owner, operator, true, 0, Timestamp::MAX, OTHER_CONTRACT_PERMIT_SIGNATURE), Err(Error::InvalidSignature));
+            assert_eq!(subgame1.permit(owner, operator, true, 0, Timestamp::MAX, PERMIT_SIGNATURE), Ok(()));
+        }
+
+        #[ink::test]
+        fn contract_uri_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.contract_uri(), None);
+            assert_eq!(subgame1.set_contract_uri([0x01, 0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.contract_uri(), Some([0x01, 0x02].to_vec()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_contract_uri([0x03].to_vec()), Err(Error::OnlyOwner));
+            assert_eq!(subgame1.contract_uri(), Some([0x01, 0x02].to_vec()));
+        }
+
+        #[ink::test]
+        fn self_approval_is_rejected_by_every_entry_point() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let signer = AccountId::from(SIGNER);
+            assert_eq!(subgame1.set_approval_for_all(accounts.alice, true), Err(Error::ApprovalForSelf));
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.alice, None, 1), Err(PSP37Error::SelfApprove));
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.alice, Some(1), 1), Err(PSP37Error::SelfApprove));
+            assert_eq!(subgame1.permit(signer, signer, true, 0, Timestamp::MAX, PERMIT_SIGNATURE), Err(Error::ApprovalForSelf));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.alice), false);
+            assert_eq!(subgame1.is_approved_for_all(signer, signer), false);
+        }
+
+        #[ink::test]
+        fn set_uri_emits_uri_event() {
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_uri(1, [0x02, 0x03].to_vec()), Ok(()));
+            assert_eq!(subgame1.uri_of(1), Some([0x02, 0x03].to_vec()));
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let event = emitted_events.last().expect("No event emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &event.data[..])
+                .expect("Cannot decode event");
+            if let Event::Uri(Uri { value, id }) = decoded_event {
+                assert_eq!(value, [0x02, 0x03].to_vec());
+                assert_eq!(id, 1);
+            } else {
+                panic!("encountered unexpected event kind: expected a Uri event")
+            }
+        }
+
+        #[ink::test]
+        fn max_mint_per_wallet_is_enforced() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_max_mint_per_wallet(1, 3), Ok(()));
+            assert_eq!(subgame1.max_mint_per_wallet_of(1), Some(3));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 2), Ok(2));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1), Ok(3));
+            assert_eq!(subgame1.minted_by_wallet_of(accounts.bob, 1), 3);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1), Err(Error::WalletMintLimitExceeded));
+            assert_eq!(subgame1.mint_batch(accounts.bob, [1].to_vec(), [1].to_vec()), Err(Error::WalletMintLimitExceeded));
+            assert_eq!(subgame1.mint(accounts.charlie, 1, 3), Ok(3));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 3);
+        }
+
+        #[ink::test]
+        fn max_mint_per_wallet_counts_minted_not_held() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_max_mint_per_wallet(1, 3), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 3), Ok(3));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 3), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1), Err(Error::WalletMintLimitExceeded));
+        }
+
+        #[ink::test]
+        fn uri_prefers_token_uri() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_base_uri(b"ipfs://base/{id}.json".to_vec()), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.create(b"ipfs://creator".to_vec()), Ok(()));
+            assert_eq!(subgame1.set_token_uri(1, b"ipfs://bob".to_vec()), Err(Error::OnlyOwner));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.set_token_uri(1, b"ipfs://one".to_vec()), Ok(()));
+            assert_eq!(subgame1.uri(1), b"ipfs://one".to_vec());
+        }
+
+        #[ink::test]
+        fn uri_falls_back_to_base_uri() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_base_uri(b"ipfs://base/{id}.json".to_vec()), Ok(()));
+            assert_eq!(subgame1.create(Vec::new()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1), Ok(1));
+            assert_eq!(subgame1.mint_with_uri(accounts.alice, 26, 1, Vec::new()), Ok(()));
+            assert_eq!(subgame1.set_token_uri(26, b"ipfs://twenty-six".to_vec()), Ok(()));
+            assert_eq!(subgame1.uri(1), b"ipfs://base/0000000000000000000000000000000000000000000000000000000000000001.json".to_vec());
+            assert_eq!(subgame1.uri(26), b"ipfs://twenty-six".to_vec());
+        }
+
+        #[ink::test]
+        fn uri_is_empty_without_token_uri_or_base_uri() {
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create(Vec::new()), Ok(()));
+            assert_eq!(subgame1.create(Vec::new()), Ok(()));
+            assert_eq!(subgame1.set_token_uri(2, b"ipfs://two".to_vec()), Ok(()));
+            assert_eq!(subgame1.uri(1), Vec::<u8>::new());
+            assert_eq!(subgame1.uri(2), b"ipfs://two".to_vec());
+        }
+
+        #[ink::test]
+        fn create_token_allocates_sequential_ids() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(10));
+            assert_eq!(subgame1.create_token(1000, accounts.bob), Ok(2));
+            assert_eq!(subgame1.create_token(500, accounts.charlie), Ok(3));
+            assert_eq!(subgame1.creator_of(2), Some(accounts.alice));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 10);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 1000);
+            assert_eq!(subgame1.balance_of(accounts.charlie, 3), 500);
+            assert_eq!(subgame1.balance_of(accounts.bob, 3), 0);
+        }
+
+        #[ink::test]
+        fn create_token_is_owner_only() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.create_token(1000, accounts.bob), Err(Error::OnlyOwner));
+            assert_eq!(subgame1.creator_of(1), None);
+        }
+
+        #[ink::test]
+        fn locked_tokens_cannot_be_transferred_before_unlock() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint_locked(accounts.bob, 1, 300, now + 1), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 400);
+            assert_eq!(subgame1.lock_of(accounts.bob, 1), (300, now + 1));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 101), Err(Error::TokensLocked));
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 100), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 1), Err(Error::TokensLocked));
+        }
+
+        #[ink::test]
+        fn locked_tokens_can_be_transferred_after_unlock() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_locked(accounts.bob, 1, 300, now + 1), Ok(()));
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 300), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 300);
+        }
+
+        #[ink::test]
+        fn market_transfer_routes_fee_to_treasury() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_treasury(accounts.frank), Ok(()));
+            assert_eq!(subgame1.set_marketplace(accounts.django, Some(10_001)), Err(Error::InvalidFee));
+            assert_eq!(subgame1.set_marketplace(accounts.django, Some(250)), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, true), Ok(false));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+            let treasury_balance = native_balance_of(accounts.frank);
+
+            set_sender_with_value(accounts.django, 249);
+            assert_eq!(subgame1.market_transfer(accounts.alice, accounts.bob, 1, 100, 10_000), Err(Error::InsufficientFee));
+
+            set_sender_with_value(accounts.django, 250);
+            assert_eq!(subgame1.market_transfer(accounts.alice, accounts.bob, 1, 100, 10_000), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 900);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 100);
+            assert_eq!(native_balance_of(accounts.frank), treasury_balance + 250);
+        }
+
+        #[ink::test]
+        fn market_transfer_rejects_unlisted_caller() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_approval_for_all(accounts.eve, true), Ok(false));
+
+            set_sender_with_value(accounts.eve, 1000);
+            assert_eq!(subgame1.market_transfer(accounts.alice, accounts.bob, 1, 100, 10_000), Err(Error::NotApprovedMarketplace));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
+        }
+
+        #[ink::test]
+        fn locked_tokens_cannot_be_transferred_or_burned() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.lock(1, 1001), Err(Error::InsufficientBalance { id: 1, available: 1000, required: 1001 }));
+            assert_eq!(subgame1.lock(1, 800), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 200);
+            assert_eq!(subgame1.locked_balance_of(accounts.alice, 1), 800);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 201), Err(Error::InsufficientBalance { id: 1, available: 200, required: 201 }));
+            assert_eq!(subgame1.burn(accounts.alice, 1, 201), Err(Error::InsufficientBalance { id: 1, available: 200, required: 201 }));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+            assert_eq!(subgame1.locked_balance_of(accounts.alice, 1), 800);
+        }
+
+        #[ink::test]
+        fn unlock_returns_tokens_to_free_balance() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.lock(1, 800), Ok(()));
+            assert_eq!(subgame1.unlock(1, 801), Err(Error::InsufficientBalance { id: 1, available: 800, required: 801 }));
+            assert_eq!(subgame1.unlock(1, 300), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 500);
+            assert_eq!(subgame1.locked_balance_of(accounts.alice, 1), 500);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 500), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 500);
+        }
+
+        #[ink::test]
+        fn default_operator_can_transfer_without_approval() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_default_operator(accounts.django, true), Ok(()));
+            assert_eq!(subgame1.is_approved_for_all(accounts.bob, accounts.django), true);
+
+            set_sender(accounts.django);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 200), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 200);
+        }
+
+        #[ink::test]
+        fn revoked_default_operator_cannot_transfer() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_default_operator(accounts.django, true), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, false), Ok(true));
+            assert_eq!(subgame1.is_approved_for_all(accounts.bob, accounts.django), false);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let event = emitted_events.last().expect("No event emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &event.data[..])
+                .expect("Cannot decode event");
+            if let Event::ApprovalForAll(ApprovalForAll { account, operator, approved }) = decoded_event {
+                assert_eq!(account, accounts.bob);
+                assert_eq!(operator, accounts.django);
+                assert_eq!(approved, false);
+            } else {
+                panic!("encountered unexpected event kind: expected an ApprovalForAll event")
+            }
+
+            set_sender(accounts.django);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 200), Err(Error::NotApproved));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, true), Ok(false));
+            assert_eq!(subgame1.is_approved_for_all(accounts.bob, accounts.django), true);
+        }
+
+        #[ink::test]
+        fn mint_also_mints_companion() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x03].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_companion_id(Some(3)), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 5), Ok(5));
+            assert_eq!(subgame1.mint_batch(accounts.bob, [2].to_vec(), [2].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 5);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 2);
+            assert_eq!(subgame1.balance_of(accounts.bob, 3), 7);
+        }
+
+        #[ink::test]
+        fn minting_companion_does_not_recurse() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_companion_id(Some(1)), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 5), Ok(5));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 5);
+        }
+
+        #[ink::test]
+        fn supply_share_bps_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.supply_share_bps(accounts.alice, 1), 0);
+            assert_eq!(subgame1.mint(accounts.alice, 1, 500), Ok(500));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 500), Ok(500));
+            assert_eq!(subgame1.supply_share_bps(accounts.alice, 1), 5000);
+            assert_eq!(subgame1.supply_share_bps(accounts.charlie, 1), 0);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 500), Ok(()));
+            assert_eq!(subgame1.supply_share_bps(accounts.bob, 1), 10_000);
+        }
+
+        #[ink::test]
+        fn array_length_mismatch_reports_lengths() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.bob, accounts.charlie].to_vec(), [1].to_vec()), Err(Error::InvalidArrayLength(3, 1)));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [1, 2].to_vec(), [1].to_vec()), Err(Error::InvalidArrayLength(2, 1)));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1].to_vec(), [1, 2, 3].to_vec()), Err(Error::InvalidArrayLength(1, 3)));
+            assert_eq!(subgame1.burn_batch(accounts.alice, [1, 2].to_vec(), Vec::new()), Err(Error::InvalidArrayLength(2, 0)));
+        }
+
+        #[ink::test]
+        fn zero_value_mint_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            let events_before = ink_env::test::recorded_events().count();
+            assert_eq!(subgame1.mint(accounts.alice, 1, 0), Err(Error::ZeroValue));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 0);
+            assert_eq!(ink_env::test::recorded_events().count(), events_before);
+        }
+
+        #[ink::test]
+        fn batch_with_zero_value_fails_atomically() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 0].to_vec()), Err(Error::ZeroValue));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 0);
+
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [1, 2].to_vec(), [0, 10].to_vec()), Err(Error::ZeroValue));
+            assert_eq!(subgame1.burn_batch(accounts.alice, [1, 2].to_vec(), [10, 0].to_vec()), Err(Error::ZeroValue));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice, accounts.bob].to_vec(), [1, 2, 2].to_vec()), Ok([1000, 1000, 0].to_vec()));
+        }
+
+        #[ink::test]
+        fn transfer_to_self_keeps_balance() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.alice, 1, 1001), Err(Error::InsufficientBalance { id: 1, available: 1000, required: 1001 }));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.alice, 1, 400), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
+            assert_eq!(subgame1.tokens_of(accounts.alice), [1].to_vec());
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let event = emitted_events.last().expect("No event emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &event.data[..])
+                .expect("Cannot decode event");
+            if let Event::TransferSingle(TransferSingle { operator, from, to, id, value }) = decoded_event {
+                assert_eq!(operator, accounts.alice);
+                assert_eq!(from, accounts.alice);
+                assert_eq!(to, accounts.alice);
+                assert_eq!(id, 1);
+                assert_eq!(value, 400);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferSingle event")
+            }
+        }
+
+        #[ink::test]
+        fn batch_transfer_sums_duplicate_ids() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [1, 2, 1].to_vec(), [300, 100, 200].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.bob, accounts.alice, accounts.bob].to_vec(), [1, 1, 2, 2].to_vec()), Ok([500, 500, 900, 100].to_vec()));
+
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [2, 1, 1].to_vec(), [100, 300, 300].to_vec()), Err(Error::InsufficientBalance { id: 1, available: 500, required: 600 }));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.bob, accounts.alice, accounts.bob].to_vec(), [1, 1, 2, 2].to_vec()), Ok([500, 500, 900, 100].to_vec()));
+        }
+
+        #[ink::test]
+        fn batch_transfer_to_self_keeps_balances() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.alice, [1, 1, 2].to_vec(), [600, 400, 1000].to_vec()), Ok(()));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.alice, [1, 1].to_vec(), [600, 401].to_vec()), Err(Error::InsufficientBalance { id: 1, available: 1000, required: 1001 }));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([1000, 1000].to_vec()));
+            assert_eq!(subgame1.tokens_of(accounts.alice), [1, 2].to_vec());
+        }
+
+        #[ink::test]
+        fn token_type_count_survives_burns() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x03].to_vec()), Ok(()));
+            assert_eq!(subgame1.token_type_count(), 0);
+
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.mint_batch(accounts.bob, [1, 2].to_vec(), [10, 20].to_vec()), Ok(()));
+            assert_eq!(subgame1.token_type_count(), 2);
+
+            assert_eq!(subgame1.burn(accounts.bob, 2, 20), Ok(0));
+            assert_eq!(PSP37::total_supply(&subgame1, None), 1);
+            assert_eq!(subgame1.token_type_count(), 2);
+
+            assert_eq!(subgame1.mint(accounts.alice, 2, 5), Ok(5));
+            assert_eq!(subgame1.mint(accounts.alice, 3, 5), Ok(5));
+            assert_eq!(subgame1.token_type_count(), 3);
+        }
+
+        #[ink::test]
+        fn transfer_fee_goes_to_recipient() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_transfer_fee(10_001, Some(accounts.django)), Err(Error::InvalidFee));
+            assert_eq!(subgame1.set_transfer_fee(250, Some(accounts.django)), Ok(()));
+            assert_eq!(subgame1.transfer_fee_bps(), 250);
+            assert_eq!(subgame1.fee_recipient(), Some(accounts.django));
+
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 400), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 600);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 390);
+            assert_eq!(subgame1.balance_of(accounts.django, 1), 10);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 1000);
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_transfer_fee(0, None), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn transfer_fee_without_recipient_is_burned() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_transfer_fee(1_000, None), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 1001), Err(Error::InsufficientBalance { id: 1, available: 1000, required: 1001 }));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 500), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 500);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 450);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 950);
+        }
+
+        #[ink::test]
+        fn zero_transfer_fee_is_a_plain_transfer() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_transfer_fee(0, Some(accounts.django)), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 400), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 600);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 400);
+            assert_eq!(subgame1.balance_of(accounts.django, 1), 0);
+        }
+
+        #[ink::test]
+        fn increase_and_decrease_allowance_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.increase_allowance(accounts.bob, 1, 100), Ok(()));
+            assert_eq!(subgame1.increase_allowance(accounts.bob, 1, 50), Ok(()));
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.bob, Some(1)), 150);
+            assert_eq!(subgame1.increase_allowance(accounts.bob, 1, TokenBalance::MAX), Err(Error::AllowanceOverflow));
+            assert_eq!(subgame1.increase_allowance(accounts.alice, 1, 1), Err(Error::ApprovalForSelf));
+
+            assert_eq!(subgame1.decrease_allowance(accounts.bob, 1, 30), Ok(()));
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.bob, Some(1)), 120);
+            assert_eq!(subgame1.decrease_allowance(accounts.bob, 1, 500), Ok(()));
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.bob, Some(1)), 0);
+        }
+
+        #[ink::test]
+        fn increase_allowance_keeps_spent_amount() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.bob, Some(1), 100), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(PSP37::transfer_from(&mut subgame1, accounts.alice, accounts.bob, 1, 60, Vec::new()), Ok(()));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.increase_allowance(accounts.bob, 1, 50), Ok(()));
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.bob, Some(1)), 90);
+
+            set_sender(accounts.bob);
+            assert_eq!(PSP37::transfer_from(&mut subgame1, accounts.alice, accounts.bob, 1, 91, Vec::new()), Err(PSP37Error::NotAllowed));
+            assert_eq!(PSP37::transfer_from(&mut subgame1, accounts.alice, accounts.bob, 1, 90, Vec::new()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 150);
+        }
+
+        #[ink::test]
+        fn set_approval_for_all_batch_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_approval_for_all_batch([accounts.bob, accounts.charlie, accounts.django].to_vec(), true), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all_batch([accounts.bob, accounts.django].to_vec(), false), Ok(()));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.charlie), true);
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.django), false);
+            assert_eq!(ink_env::test::recorded_events().count(), 5);
+        }
+
+        #[ink::test]
+        fn set_approval_for_all_batch_rejects_caller() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_approval_for_all_batch([accounts.bob, accounts.alice].to_vec(), true), Err(Error::ApprovalForSelf));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+            assert_eq!(ink_env::test::recorded_events().count(), 0);
+        }
+
+        #[ink::test]
+        fn revoke_all_operators_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_default_operator(accounts.eve, true), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_approval_for_all_batch([accounts.alice, accounts.charlie, accounts.django].to_vec(), true), Ok(()));
+            assert_eq!(subgame1.revoke_all_operators(), Ok(()));
+            for operator in [accounts.alice, accounts.charlie, accounts.django, accounts.eve].iter() {
+                assert_eq!(subgame1.is_approved_for_all(accounts.bob, *operator), false);
+            }
+            assert_eq!(subgame1.approved_operators.get(&accounts.bob), None);
+        }
+
+        #[ink::test]
+        fn approval_expires_after_deadline() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_approval_for_all_until(accounts.bob, true, now), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all(accounts.charlie, true), Ok(false));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), true);
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 100), Ok(()));
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.charlie), true);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 100), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn wrap_and_unwrap_call_the_wrapper() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let wrapper = AccountId::from([0x10; 32]);
+            let mut subgame1 = Subgame1::with_wrapper(wrapper, 1);
+            assert_eq!(subgame1.wrapper(), Some((wrapper, 1)));
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.bob, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.wrap(2, 100), Err(Error::NoWrapper));
+            assert_eq!(subgame1.wrap(1, 1001), Err(Error::InsufficientBalance { id: 1, available: 1000, required: 1001 }));
+            assert_eq!(subgame1.wrap(1, 400), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 600);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 600);
+            assert_eq!(mock_erc20::balance_of(accounts.bob), 400);
+
+            assert_eq!(subgame1.unwrap(1, 401), Err(Error::WrapperCallFailed));
+            assert_eq!(subgame1.unwrap(1, 150), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 750);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 750);
+            assert_eq!(mock_erc20::balance_of(accounts.bob), 250);
+
+            assert_eq!(mock_erc20::calls(), [
+                (wrapper, WRAPPER_MINT_SELECTOR, accounts.bob, 400),
+                (wrapper, WRAPPER_BURN_SELECTOR, accounts.bob, 401),
+                (wrapper, WRAPPER_BURN_SELECTOR, accounts.bob, 150),
+            ].to_vec());
+        }
+
+        #[ink::test]
+        fn wrap_without_wrapper_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.wrap(1, 100), Err(Error::NoWrapper));
+            assert_eq!(subgame1.unwrap(1, 100), Err(Error::NoWrapper));
+            assert!(mock_erc20::calls().is_empty());
+        }
+
+        #[ink::test]
+        fn recover_returns_tokens_sent_to_the_contract() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_accept_contract_transfers(true), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, contract_id(), 1, 300), Ok(()));
+            assert_eq!(subgame1.recover(1, accounts.bob, 300), Err(Error::OnlyOwner));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.recover(1, AccountId::from([0x0; 32]), 300), Err(Error::NotApproved));
+            assert_eq!(subgame1.recover(1, accounts.charlie, 301), Err(Error::InsufficientBalance { id: 1, available: 300, required: 301 }));
+            assert_eq!(subgame1.recover(1, accounts.charlie, 300), Ok(()));
+            assert_eq!(subgame1.balance_of(contract_id(), 1), 0);
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 300);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 700);
+        }
+
+        #[ink::test]
+        fn balance_of_at_keeps_snapshot_balances() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.balance_of_at(accounts.alice, 1, 1), Err(Error::InvalidSnapshot));
+            assert_eq!(subgame1.snapshot(), Ok(1));
+
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 300), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 100), Ok(()));
+            assert_eq!(subgame1.snapshot(), Ok(2));
+            assert_eq!(subgame1.snapshot(), Ok(3));
+            assert_eq!(subgame1.burn(accounts.bob, 1, 50), Ok(350));
+
+            assert_eq!(subgame1.balance_of_at(accounts.alice, 1, 1), Ok(1000));
+            assert_eq!(subgame1.balance_of_at(accounts.bob, 1, 1), Ok(0));
+            assert_eq!(subgame1.balance_of_at(accounts.alice, 1, 2), Ok(600));
+            assert_eq!(subgame1.balance_of_at(accounts.bob, 1, 2), Ok(400));
+            assert_eq!(subgame1.balance_of_at(accounts.bob, 1, 3), Ok(400));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 350);
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.snapshot(), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn mint_nft_allocates_unique_instances() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            let sword = subgame1.create_nft_type([0x02].to_vec()).expect("Cannot create type");
+            let shield = subgame1.create_nft_type([0x03].to_vec()).expect("Cannot create type");
+            assert_ne!(sword, shield);
+            assert_eq!(subgame1.is_nft(sword), false);
+            assert_eq!(subgame1.is_nft(1), false);
+            assert_eq!(subgame1.base_type(1), 0);
+
+            let first = subgame1.mint_nft(sword, accounts.alice).expect("Cannot mint nft");
+            let second = subgame1.mint_nft(sword, accounts.bob).expect("Cannot mint nft");
+            let third = subgame1.mint_nft(sword, accounts.bob).expect("Cannot mint nft");
+            assert_eq!(subgame1.mint_nft(shield, accounts.alice), Ok(shield | 1));
+            assert!(first != second && second != third && first != third);
+            for (id, index) in [(first, 1), (second, 2), (third, 3)].iter() {
+                assert_eq!(subgame1.is_nft(*id), true);
+                assert_eq!(subgame1.base_type(*id), sword);
+                assert_eq!(subgame1.index(*id), *index);
+                assert_eq!(PSP37::total_supply(&subgame1, Some(*id)), 1);
+            }
+            assert_eq!(subgame1.balance_of(accounts.bob, second), 1);
+            assert_eq!(subgame1.balance_of(accounts.bob, third), 1);
+
+            assert_eq!(subgame1.mint_nft(1, accounts.alice), Err(Error::NotNftType));
+            assert_eq!(subgame1.mint_nft(first, accounts.alice), Err(Error::NotNftType));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.mint_nft(sword, accounts.bob), Err(Error::OnlyCreator));
+        }
+
+        #[ink::test]
+        fn nft_instances_are_non_fungible() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let sword = subgame1.create_nft_type([0x01].to_vec()).expect("Cannot create type");
+            let first = subgame1.mint_nft(sword, accounts.alice).expect("Cannot mint nft");
+            assert_eq!(subgame1.mint(accounts.alice, first, 1), Err(Error::InvalidNftMint));
+            assert_eq!(subgame1.mint(accounts.alice, sword, 1), Err(Error::InvalidNftMint));
+            assert_eq!(subgame1.mint(accounts.alice, sword | 2, 1), Err(Error::InvalidNftMint));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, first, 2), Err(Error::InsufficientBalance { id: first, available: 1, required: 2 }));
+
+            assert_eq!(subgame1.mint(accounts.bob, first, 2), Err(Error::InvalidNftMint));
+        }
+
+        #[ink::test]
+        fn burned_nft_instances_cannot_be_reminted() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let sword = subgame1.create_nft_type([0x01].to_vec()).expect("Cannot create type");
+            let first = subgame1.mint_nft(sword, accounts.alice).expect("Cannot mint nft");
+
+            assert_eq!(subgame1.burn(accounts.alice, first, 1), Ok(0));
+            assert_eq!(subgame1.total_burned(first), 1);
+            assert_eq!(subgame1.mint(accounts.bob, first, 1), Err(Error::InvalidNftMint));
+            assert_eq!(subgame1.balance_of(accounts.bob, first), 0);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(first)), 0);
+            assert_eq!(subgame1.mint_nft(sword, accounts.bob), Ok(sword | 2));
+        }
+
+        #[ink::test]
+        fn ids_above_u32_max_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let id = TokenId::from(u32::MAX) + 1;
+            subgame1.next_token_id = id;
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, id, 1000), Ok(1000));
+            assert_eq!(subgame1.balance_of(accounts.alice, id), 1000);
+            assert_eq!(subgame1.balance_of(accounts.alice, 0), 0);
+
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, id, 300), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.bob].to_vec(), [id, id].to_vec()), Ok([700, 300].to_vec()));
+            assert_eq!(subgame1.tokens_of(accounts.bob), [id].to_vec());
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let event = emitted_events.last().expect("No event emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &event.data[..])
+                .expect("Cannot decode event");
+            if let Event::TransferSingle(TransferSingle { id: event_id, .. }) = decoded_event {
+                assert_eq!(event_id, id);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferSingle event")
+            }
+        }
+
+        #[ink::test]
+        fn token_id_scale_round_trips() {
+            let id: TokenId = u128::MAX - 1;
+            let encoded = id.encode();
+            assert_eq!(encoded.len(), 16);
+            assert_eq!(<TokenId as Decode>::decode(&mut &encoded[..]), Ok(id));
+        }
+
+        #[ink::test]
+        fn set_approval_for_all_returns_previous_state() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(true));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, false), Ok(true));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, false), Ok(false));
+            assert_eq!(ink_env::test::recorded_events().count(), 4);
+        }
+
+        #[ink::test]
+        fn refresh_metadata_emits_batch_update() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.refresh_metadata(10, 9), Err(Error::InvalidRange));
+            assert_eq!(subgame1.refresh_metadata(1, 100), Ok(()));
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 1);
+            let decoded_event = <Event as Decode>::decode(&mut &emitted_events[0].data[..])
+                .expect("Cannot decode event");
+            if let Event::BatchMetadataUpdate(BatchMetadataUpdate { from_id, to_id }) = decoded_event {
+                assert_eq!(from_id, 1);
+                assert_eq!(to_id, 100);
+            } else {
+                panic!("encountered unexpected event kind: expected a BatchMetadataUpdate event")
+            }
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.refresh_metadata(1, 1), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn new_with_supply_mints_to_deployer() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new_with_supply([1, 2].to_vec(), [1000, 50].to_vec());
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([1000, 50].to_vec()));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 1000);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let event = emitted_events.last().expect("No event emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &event.data[..])
+                .expect("Cannot decode event");
+            if let Event::TransferBatch(TransferBatch { to, ids, values, .. }) = decoded_event {
+                assert_eq!(to, accounts.alice);
+                assert_eq!(ids, [1, 2].to_vec());
+                assert_eq!(values, [1000, 50].to_vec());
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferBatch event")
+            }
+
+            assert_eq!(subgame1.mint(accounts.bob, 2, 10), Ok(10));
+            assert_eq!(subgame1.create([0x03].to_vec()), Ok(()));
+            assert_eq!(subgame1.token_creator.get(&3), Some(&accounts.alice));
+        }
+
+        #[ink::test]
+        #[should_panic(expected = "ids and values must have the same length")]
+        fn new_with_supply_rejects_length_mismatch() {
+            Subgame1::new_with_supply([1, 2].to_vec(), [1000].to_vec());
+        }
+
+        #[ink::test]
+        fn multi_token_consumer_transfers_through_the_trait() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(MultiToken::mint(&mut subgame1, accounts.alice, 1, 1000), Ok(()));
+
+            let mut arena = Arena { token: subgame1, prize_pool: accounts.alice };
+            assert_eq!(arena.award(accounts.bob, 1, 300), Ok(()));
+            assert_eq!(arena.award(accounts.bob, 1, 701), Err(Error::InsufficientBalance { id: 1, available: 700, required: 701 }));
+            assert_eq!(MultiToken::balance_of(&arena.token, accounts.alice, 1), 700);
+            assert_eq!(MultiToken::balance_of(&arena.token, accounts.bob, 1), 300);
+
+            assert_eq!(MultiToken::burn(&mut arena.token, accounts.bob, 1, 100), Ok(()));
+            assert_eq!(MultiToken::balance_of(&arena.token, accounts.bob, 1), 200);
+        }
+
+        #[ink::test]
+        fn admin_burn_burns_across_accounts() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint(accounts.charlie, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint(accounts.django, 2, 100), Ok(100));
+            let events_before = ink_env::test::recorded_events().count();
+
+            assert_eq!(subgame1.admin_burn([accounts.bob, accounts.charlie, accounts.django].to_vec(), [1, 1, 2].to_vec(), [10, 20, 30].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.bob, accounts.charlie, accounts.django].to_vec(), [1, 1, 2].to_vec()), Ok([90, 80, 70].to_vec()));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 170);
+            assert_eq!(ink_env::test::recorded_events().count(), events_before + 6);
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.admin_burn([accounts.bob].to_vec(), [1].to_vec(), [10].to_vec()), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn admin_burn_rejects_mismatched_lengths() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.admin_burn([accounts.bob, accounts.charlie].to_vec(), [1].to_vec(), [10].to_vec()), Err(Error::InvalidArrayLength(2, 1)));
+            assert_eq!(subgame1.admin_burn([accounts.bob].to_vec(), [1].to_vec(), [10, 20].to_vec()), Err(Error::InvalidArrayLength(1, 2)));
+            assert_eq!(subgame1.admin_burn([AccountId::from([0x0; 32])].to_vec(), [1].to_vec(), [10].to_vec()), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn admin_burn_aborts_on_insufficient_balance() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint(accounts.charlie, 1, 100), Ok(100));
+            assert_eq!(subgame1.admin_burn([accounts.bob, accounts.charlie].to_vec(), [1, 1].to_vec(), [50, 101].to_vec()), Err(Error::InsufficientBalance { id: 1, available: 100, required: 101 }));
+            assert_eq!(subgame1.admin_burn([accounts.bob, accounts.bob].to_vec(), [1, 1].to_vec(), [60, 60].to_vec()), Err(Error::InsufficientBalance { id: 1, available: 100, required: 120 }));
+            assert_eq!(subgame1.balance_of_batch([accounts.bob, accounts.charlie].to_vec(), [1, 1].to_vec()), Ok([100, 100].to_vec()));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 200);
+        }
+
+        #[ink::test]
+        fn operator_can_transfer_on_behalf_of_owner() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.charlie, 1, 250), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 750);
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 250);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let event = emitted_events.last().expect("No event emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &event.data[..])
+                .expect("Cannot decode event");
+            if let Event::TransferSingle(TransferSingle { operator, from, to, id, value }) = decoded_event {
+                assert_eq!(operator, accounts.bob);
+                assert_eq!(from, accounts.alice);
+                assert_eq!(to, accounts.charlie);
+                assert_eq!(id, 1);
+                assert_eq!(value, 250);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferSingle event")
+            }
+        }
+
+        #[ink::test]
+        fn non_operator_cannot_transfer() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 1), Err(Error::NotApproved));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [1].to_vec(), [1].to_vec()), Err(Error::NotApproved));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, false), Ok(true));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 1), Err(Error::NotApproved));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+        }
+
+        #[ink::test]
+        fn batch_transfer_accounts_balances_and_emits_event() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 500].to_vec()), Ok(()));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [1, 2].to_vec(), [100, 500].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice, accounts.bob, accounts.bob].to_vec(), [1, 2, 1, 2].to_vec()), Ok([900, 0, 100, 500].to_vec()));
+            assert_eq!(subgame1.tokens_of(accounts.alice), [1].to_vec());
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 1000);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(2)), 500);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let event = emitted_events.last().expect("No event emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &event.data[..])
+                .expect("Cannot decode event");
+            if let Event::TransferBatch(TransferBatch { operator, from, to, ids, values }) = decoded_event {
+                assert_eq!(operator, accounts.alice);
+                assert_eq!(from, accounts.alice);
+                assert_eq!(to, accounts.bob);
+                assert_eq!(ids, [1, 2].to_vec());
+                assert_eq!(values, [100, 500].to_vec());
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferBatch event")
+            }
+        }
+
+        #[ink::test]
+        fn mint_and_burn_update_supply() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1000));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 1000);
+            assert_eq!(subgame1.burn(accounts.bob, 1, 400), Ok(600));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 600);
+            assert_eq!(subgame1.burn(accounts.bob, 1, 601), Err(Error::InsufficientBalance { id: 1, available: 600, required: 601 }));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 600);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let event = emitted_events.last().expect("No event emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &event.data[..])
+                .expect("Cannot decode event");
+            if let Event::TransferSingle(TransferSingle { from, to, value, .. }) = decoded_event {
+                assert_eq!(from, accounts.bob);
+                assert_eq!(to, AccountId::from([0x0; 32]));
+                assert_eq!(value, 400);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferSingle event")
+            }
+        }
+
+        #[ink::test]
+        fn insufficient_balance_reports_the_failing_id() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x03].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2, 3].to_vec(), [100, 40, 100].to_vec()), Ok(()));
+            assert_eq!(
+                subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [1, 2, 3].to_vec(), [10, 50, 10].to_vec()),
+                Err(Error::InsufficientBalance { id: 2, available: 40, required: 50 })
+            );
+            assert_eq!(
+                subgame1.safe_transfer_from(accounts.alice, accounts.bob, 3, 150),
+                Err(Error::InsufficientBalance { id: 3, available: 100, required: 150 })
+            );
+            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice, accounts.alice].to_vec(), [1, 2, 3].to_vec()), Ok([100, 40, 100].to_vec()));
+        }
+
+        #[ink::test]
+        fn deposit_mints_wrapped_native() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+
+            set_sender_with_value(accounts.bob, 0);
+            assert_eq!(subgame1.deposit(), Err(Error::ZeroValue));
+
+            set_sender_with_value(accounts.bob, 500);
+            assert_eq!(subgame1.deposit(), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, WRAPPED_NATIVE_ID), 500);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(WRAPPED_NATIVE_ID)), 500);
+        }
+
+        #[ink::test]
+        fn withdraw_burns_wrapped_native() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+
+            set_sender_with_value(accounts.bob, 500);
+            assert_eq!(subgame1.deposit(), Ok(()));
+            let bob_balance = native_balance_of(accounts.bob);
+
+            set_sender(accounts.bob);
+            assert_eq!(
+                subgame1.withdraw(501),
+                Err(Error::InsufficientBalance { id: WRAPPED_NATIVE_ID, available: 500, required: 501 })
+            );
+            assert_eq!(subgame1.withdraw(200), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, WRAPPED_NATIVE_ID), 300);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(WRAPPED_NATIVE_ID)), 300);
+            assert_eq!(native_balance_of(accounts.bob), bob_balance + 200);
+        }
+
+        #[ink::test]
+        fn transfers_to_the_contract_are_blocked_by_default() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.accepts_contract_transfers(), false);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, contract_id(), 1, 100), Err(Error::TransferToSelfContract));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, contract_id(), [1].to_vec(), [100].to_vec()), Err(Error::TransferToSelfContract));
+            assert_eq!(subgame1.balance_of(contract_id(), 1), 0);
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_accept_contract_transfers(true), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn allowlist_is_ignored_when_not_enforced() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.enforces_allowlist(), false);
+            assert_eq!(subgame1.is_operator_allowed(accounts.bob), false);
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), true);
+        }
+
+        #[ink::test]
+        fn enforced_allowlist_rejects_unlisted_operators() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, true), Ok(false));
+            assert_eq!(subgame1.set_enforce_allowlist(true), Ok(()));
+            assert_eq!(subgame1.set_operator_allowed(accounts.bob, true), Ok(()));
+            assert_eq!(subgame1.is_operator_allowed(accounts.bob), true);
+
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+            assert_eq!(subgame1.set_approval_for_all(accounts.charlie, true), Err(Error::OperatorNotAllowed));
+            assert_eq!(subgame1.set_approval_for_all_batch([accounts.bob, accounts.charlie].to_vec(), true), Err(Error::OperatorNotAllowed));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.charlie), false);
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, false), Ok(true));
+
+            assert_eq!(subgame1.set_operator_allowed(accounts.bob, false), Ok(()));
+            assert_eq!(subgame1.is_operator_allowed(accounts.bob), false);
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Err(Error::OperatorNotAllowed));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_operator_allowed(accounts.bob, true), Err(Error::OnlyOwner));
+            assert_eq!(subgame1.set_enforce_allowlist(false), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn total_balance_of_sums_all_ids() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x03].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2, 3].to_vec(), [100, 20, 3].to_vec()), Ok(()));
+            assert_eq!(subgame1.total_balance_of(accounts.alice), 123);
+            assert_eq!(subgame1.total_balance_of(accounts.bob), 0);
+
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 2, 20), Ok(()));
+            assert_eq!(subgame1.total_balance_of(accounts.alice), 103);
+            assert_eq!(subgame1.total_balance_of(accounts.bob), 20);
+        }
+
+        #[ink::test]
+        fn total_balance_of_saturates() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [TokenBalance::MAX, 1].to_vec()), Ok(()));
+            assert_eq!(subgame1.total_balance_of(accounts.alice), TokenBalance::MAX);
+        }
+
+        #[ink::test]
+        fn claim_is_limited_by_cap() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_claim_cap(accounts.bob, 1, 5), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_claim_cap(accounts.bob, 1, 100), Err(Error::OnlyOwner));
+            assert_eq!(subgame1.claim(1, 2), Ok(()));
+            assert_eq!(subgame1.claim_cap_of(accounts.bob, 1), 3);
+            assert_eq!(subgame1.claim(1, 4), Err(Error::ClaimCapExceeded));
+            assert_eq!(subgame1.claim(1, 3), Ok(()));
+            assert_eq!(subgame1.claim_cap_of(accounts.bob, 1), 0);
+            assert_eq!(subgame1.claim(1, 1), Err(Error::ClaimCapExceeded));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 5);
+
+            set_sender(accounts.charlie);
+            assert_eq!(subgame1.claim(1, 1), Err(Error::ClaimCapExceeded));
+        }
+
+        #[ink::test]
+        fn admin_burn_sequences_its_transfers() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint(accounts.charlie, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint(accounts.django, 1, 100), Ok(100));
+            let events_before = ink_env::test::recorded_events().count();
+            assert_eq!(subgame1.admin_burn([accounts.bob, accounts.charlie, accounts.django].to_vec(), [1, 1, 1].to_vec(), [1, 2, 3].to_vec()), Ok(()));
+
+            let emitted_events = ink_env::test::recorded_events().skip(events_before).collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 6);
+            for (i, pair) in emitted_events.chunks(2).enumerate() {
+                let transfer = <Event as Decode>::decode(&mut &pair[0].data[..])
+                    .expect("Cannot decode event");
+                let sequence = <Event as Decode>::decode(&mut &pair[1].data[..])
+                    .expect("Cannot decode event");
+                match (transfer, sequence) {
+                    (Event::TransferSingle(TransferSingle { value, .. }), Event::BatchSequence(BatchSequence { operator, seq, batch_len })) => {
+                        assert_eq!(value, i as TokenBalance + 1);
+                        assert_eq!(operator, accounts.alice);
+                        assert_eq!(seq, i as u32);
+                        assert_eq!(batch_len, 3);
+                    }
+                    _ => panic!("encountered unexpected event kinds: expected TransferSingle then BatchSequence"),
+                }
+            }
+        }
+
+        #[ink::test]
+        fn mint_with_uri_sets_metadata() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, 7, 1, [0x07].to_vec()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 7), 1);
+            assert_eq!(subgame1.uri(7), [0x07].to_vec());
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let decoded_events = emitted_events.iter().rev().take(2)
+                .map(|event| <Event as Decode>::decode(&mut &event.data[..]).expect("Cannot decode event"))
+                .collect::<Vec<_>>();
+            match (&decoded_events[1], &decoded_events[0]) {
+                (Event::TransferSingle(TransferSingle { to, id, value, .. }), Event::Uri(Uri { value: uri, id: uri_id })) => {
+                    assert_eq!((*to, *id, *value), (accounts.bob, 7, 1));
+                    assert_eq!((uri.clone(), *uri_id), ([0x07].to_vec(), 7));
+                }
+                _ => panic!("encountered unexpected event kinds: expected TransferSingle then Uri"),
+            }
+
+            assert_eq!(subgame1.create([0x08].to_vec()), Ok(()));
+            assert_eq!(subgame1.uri(8), [0x08].to_vec());
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, 9, 1, [0x09].to_vec()), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn mint_with_uri_rejects_overwrite() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, 1, 1, [0x02].to_vec()), Err(Error::UriAlreadySet));
+            assert_eq!(subgame1.uri(1), [0x01].to_vec());
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, 5, 1, [0x05].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, 5, 1, [0x06].to_vec()), Err(Error::UriAlreadySet));
+            assert_eq!(subgame1.uri(5), [0x05].to_vec());
+        }
+
+        #[ink::test]
+        fn redeem_burns_inputs_and_emits_event() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+            assert_eq!(subgame1.mint(accounts.bob, 2, 5), Ok(5));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.redeem(vec![1, 2], vec![3, 2], 42), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 7);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 3);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 7);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::Redeemed(Redeemed { account, ids, values, recipe_id }) = decoded_event {
+                assert_eq!(account, accounts.bob);
+                assert_eq!(ids, vec![1, 2]);
+                assert_eq!(values, vec![3, 2]);
+                assert_eq!(recipe_id, 42);
+            } else {
+                panic!("encountered unexpected event kind: expected a Redeemed event")
+            }
+
+            assert_eq!(subgame1.redeem(vec![1], vec![1, 1], 42), Err(Error::InvalidArrayLength(1, 2)));
+        }
+
+        #[ink::test]
+        fn redeem_with_insufficient_input_burns_nothing() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+            assert_eq!(subgame1.mint(accounts.bob, 2, 1), Ok(1));
+
+            set_sender(accounts.bob);
+            assert_eq!(
+                subgame1.redeem(vec![1, 2], vec![3, 2], 7),
+                Err(Error::InsufficientBalance { id: 2, available: 1, required: 2 })
+            );
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 10);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 1);
+        }
+
+        #[ink::test]
+        fn zero_accounts_are_rejected() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let zero = AccountId::from(ZERO_ACCOUNT);
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(10));
+
+            assert_eq!(subgame1.safe_transfer_from(zero, accounts.bob, 1, 1), Err(Error::NotApproved));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, zero, 1, 1), Err(Error::NotApproved));
+            assert_eq!(subgame1.safe_batch_transfer_from(zero, accounts.bob, vec![1], vec![1]), Err(Error::NotApproved));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, zero, vec![1], vec![1]), Err(Error::NotApproved));
+            assert_eq!(subgame1.mint(zero, 1, 1), Err(Error::NotApproved));
+            assert_eq!(subgame1.mint_batch(zero, vec![1], vec![1]), Err(Error::NotApproved));
+            assert_eq!(subgame1.burn(zero, 1, 1), Err(Error::NotApproved));
+            assert_eq!(subgame1.burn_batch(zero, vec![1], vec![1]), Err(Error::NotApproved));
+
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 10);
+            assert_eq!(subgame1.balance_of(zero, 1), 0);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 10);
+        }
+
+        #[ink::test]
+        fn operators_of_lists_current_approvals() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.operators_of(accounts.alice), Vec::<AccountId>::new());
+
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+            assert_eq!(subgame1.set_approval_for_all(accounts.charlie, true), Ok(false));
+            assert_eq!(subgame1.operators_of(accounts.alice), vec![accounts.bob, accounts.charlie]);
+
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, false), Ok(true));
+            assert_eq!(subgame1.operators_of(accounts.alice), vec![accounts.charlie]);
+            assert_eq!(subgame1.operators_of(accounts.bob), Vec::<AccountId>::new());
+        }
+
+        #[ink::test]
+        fn mint_voucher_works() {
+            let owner = AccountId::from(SIGNER);
+            let buyer = AccountId::from([0x02; 32]);
+            set_sender(owner);
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 100)
+                .expect("Cannot set account balance");
+
+            set_sender_with_value(buyer, 99);
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, VOUCHER_SIGNATURE), Err(Error::InsufficientPayment));
+
+            set_sender_with_value(buyer, 100);
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 6, 100, 0, VOUCHER_SIGNATURE), Err(Error::InvalidVoucherSignature));
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, VOUCHER_SIGNATURE), Ok(()));
+            assert_eq!(subgame1.balance_of(buyer, 1), 5);
+            assert_eq!(native_balance_of(owner), 100);
+        }
+
+        #[ink::test]
+        fn mint_voucher_replay_fails() {
+            let owner = AccountId::from(SIGNER);
+            let buyer = AccountId::from([0x02; 32]);
+            set_sender(owner);
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 200)
+                .expect("Cannot set account balance");
+
+            set_sender_with_value(buyer, 100);
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, VOUCHER_SIGNATURE), Ok(()));
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, VOUCHER_SIGNATURE), Err(Error::VoucherAlreadyRedeemed));
+            assert_eq!(subgame1.balance_of(buyer, 1), 5);
+        }
+
+        #[ink::test]
+        fn mint_voucher_for_other_contract_fails() {
+            let owner = AccountId::from(SIGNER);
+            let buyer = AccountId::from([0x02; 32]);
+            set_sender(owner);
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 100)
+                .expect("Cannot set account balance");
+
+            set_sender_with_value(buyer, 100);
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, OTHER_CONTRACT_VOUCHER_SIGNATURE), Err(Error::InvalidVoucherSignature));
+            assert_eq!(subgame1.balance_of(buyer, 1), 0);
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, VOUCHER_SIGNATURE), Ok(()));
+        }
+
+        #[ink::test]
+        fn mint_voucher_rejects_unregistered_ids() {
+            let owner = AccountId::from(SIGNER);
+            let buyer = AccountId::from([0x02; 32]);
+            set_sender(owner);
+            let mut subgame1 = Subgame1::new();
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 100)
+                .expect("Cannot set account balance");
+            let owner_before = native_balance_of(owner);
+
+            set_sender_with_value(buyer, 100);
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, VOUCHER_SIGNATURE), Err(Error::TokenNotFound));
+            assert_eq!(subgame1.balance_of(buyer, 1), 0);
+            assert_eq!(native_balance_of(owner), owner_before);
+        }
+
+        #[ink::test]
+        fn mint_voucher_fails_after_renounce() {
+            let owner = AccountId::from(SIGNER);
+            let buyer = AccountId::from([0x02; 32]);
+            set_sender(owner);
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.renounce_ownership(), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 100)
+                .expect("Cannot set account balance");
+
+            set_sender_with_value(buyer, 100);
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, VOUCHER_SIGNATURE), Err(Error::InvalidVoucherSignature));
+            assert_eq!(native_balance_of(contract_id()), 100);
+        }
+
+        #[ink::test]
+        fn revoked_voucher_cannot_be_redeemed() {
+            let owner = AccountId::from(SIGNER);
+            let buyer = AccountId::from([0x02; 32]);
+            set_sender(owner);
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+
+            set_sender(buyer);
+            assert_eq!(subgame1.revoke_voucher(0), Err(Error::OnlyOwner));
+
+            set_sender(owner);
+            assert_eq!(subgame1.revoke_voucher(0), Ok(()));
+            assert_eq!(subgame1.revoke_voucher(0), Err(Error::VoucherAlreadyRedeemed));
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::VoucherRevoked(VoucherRevoked { nonce }) = decoded_event {
+                assert_eq!(nonce, 0);
+            } else {
+                panic!("encountered unexpected event kind: expected a VoucherRevoked event")
+            }
+
+            set_sender_with_value(buyer, 100);
+            assert_eq!(subgame1.mint_voucher(buyer, 1, 5, 100, 0, VOUCHER_SIGNATURE), Err(Error::VoucherAlreadyRedeemed));
+            assert_eq!(subgame1.balance_of(buyer, 1), 0);
+        }
+
+        #[ink::test]
+        fn balances_and_supplies_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+            assert_eq!(subgame1.mint(accounts.charlie, 1, 5), Ok(5));
+            assert_eq!(subgame1.mint(accounts.charlie, 2, 7), Ok(7));
+
+            assert_eq!(
+                subgame1.balances_and_supplies(accounts.bob, vec![1, 2, 3]),
+                vec![(10, 15), (0, 7), (0, 0)]
+            );
+            assert_eq!(subgame1.balances_and_supplies(accounts.bob, Vec::new()), Vec::new());
+        }
+
+        #[ink::test]
+        fn batches_are_capped_at_max_batch_size() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+
+            let ids = vec![1; MAX_BATCH_SIZE];
+            let values = vec![1; MAX_BATCH_SIZE];
+            let too_many_ids = vec![1; MAX_BATCH_SIZE + 1];
+            let too_many_values = vec![1; MAX_BATCH_SIZE + 1];
+
+            assert_eq!(subgame1.mint_batch(accounts.alice, too_many_ids.clone(), too_many_values.clone()), Err(Error::BatchTooLarge));
+            assert_eq!(subgame1.mint_batch(accounts.alice, ids.clone(), values.clone()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), MAX_BATCH_SIZE as TokenBalance);
+
+            assert_eq!(
+                subgame1.balance_of_batch(vec![accounts.alice; MAX_BATCH_SIZE + 1], too_many_ids.clone()),
+                Err(Error::BatchTooLarge)
+            );
+            assert_eq!(
+                subgame1.balance_of_batch(vec![accounts.alice; MAX_BATCH_SIZE], ids.clone()),
+                Ok(vec![MAX_BATCH_SIZE as TokenBalance; MAX_BATCH_SIZE])
+            );
+
+            assert_eq!(
+                subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, too_many_ids.clone(), too_many_values.clone()),
+                Err(Error::BatchTooLarge)
+            );
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, ids.clone(), values.clone()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), MAX_BATCH_SIZE as TokenBalance);
+
+            assert_eq!(subgame1.burn_batch(accounts.bob, too_many_ids, too_many_values), Err(Error::BatchTooLarge));
+            assert_eq!(subgame1.burn_batch(accounts.bob, ids, values), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+        }
+
+        #[ink::test]
+        fn are_approved_for_all_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+            set_sender(accounts.charlie);
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, true), Ok(false));
+
+            assert_eq!(
+                subgame1.are_approved_for_all(
+                    vec![accounts.alice, accounts.alice, accounts.charlie, accounts.charlie],
+                    vec![accounts.bob, accounts.django, accounts.django, accounts.bob],
+                ),
+                Ok(vec![true, false, true, false])
+            );
+            assert_eq!(
+                subgame1.are_approved_for_all(vec![accounts.alice], vec![accounts.bob, accounts.charlie]),
+                Err(Error::InvalidArrayLength(1, 2))
+            );
+        }
+
+        #[ink::test]
+        fn transfer_cooldown_blocks_immediate_retransfer() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_transfer_cooldown(1, 1), Ok(()));
+            assert_eq!(subgame1.transfer_cooldown_of(1), 1);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_transfer_cooldown(1, 0), Err(Error::OnlyCreator));
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 5), Err(Error::TransferCooldown));
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 5), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 5);
+
+            set_sender(accounts.charlie);
+            assert_eq!(subgame1.safe_transfer_from(accounts.charlie, accounts.django, 1, 5), Err(Error::TransferCooldown));
+        }
+
+        #[ink::test]
+        fn transfer_cooldown_can_be_disabled() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_transfer_cooldown(1, 1), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+            assert_eq!(subgame1.set_transfer_cooldown(1, 0), Ok(()));
+            assert_eq!(subgame1.transfer_cooldown_of(1), 0);
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 5), Ok(()));
+        }
+
+        #[ink::test]
+        fn ownership_transfer_needs_acceptance() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.transfer_ownership(AccountId::from(ZERO_ACCOUNT)), Err(Error::NotApproved));
+            assert_eq!(subgame1.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(subgame1.owner(), accounts.alice);
+            assert_eq!(subgame1.pending_owner(), Some(accounts.bob));
+
+            set_sender(accounts.charlie);
+            assert_eq!(subgame1.accept_ownership(), Err(Error::NotPendingOwner));
+            assert_eq!(subgame1.transfer_ownership(accounts.charlie), Err(Error::OnlyOwner));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.accept_ownership(), Ok(()));
+            assert_eq!(subgame1.owner(), accounts.bob);
+            assert_eq!(subgame1.get_owner(), accounts.bob);
+            assert_eq!(subgame1.pending_owner(), None);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::OwnershipTransferred(OwnershipTransferred { previous_owner, new_owner }) = decoded_event {
+                assert_eq!(previous_owner, accounts.alice);
+                assert_eq!(new_owner, accounts.bob);
+            } else {
+                panic!("encountered unexpected event kind: expected an OwnershipTransferred event")
+            }
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.freeze(accounts.charlie), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn renounce_ownership_leaves_no_owner() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.transfer_ownership(accounts.bob), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.renounce_ownership(), Err(Error::OnlyOwner));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.renounce_ownership(), Ok(()));
+            assert_eq!(subgame1.owner(), AccountId::from(ZERO_ACCOUNT));
+            assert_eq!(subgame1.pending_owner(), None);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::OwnershipTransferred(OwnershipTransferred { previous_owner, new_owner }) = decoded_event {
+                assert_eq!(previous_owner, accounts.alice);
+                assert_eq!(new_owner, AccountId::from(ZERO_ACCOUNT));
+            } else {
+                panic!("encountered unexpected event kind: expected an OwnershipTransferred event")
+            }
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.accept_ownership(), Err(Error::NotPendingOwner));
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.freeze(accounts.charlie), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn key_of_is_deterministic_and_distinct() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let key = Subgame1::key_of(BALANCES_PREFIX, &(accounts.alice, 1 as TokenId));
+            assert_eq!(key, Subgame1::key_of(BALANCES_PREFIX, &(accounts.alice, 1 as TokenId)));
+            assert_ne!(key, Subgame1::key_of(BALANCES_PREFIX, &(accounts.alice, 2 as TokenId)));
+            assert_ne!(key, Subgame1::key_of(BALANCES_PREFIX, &(accounts.bob, 1 as TokenId)));
+
+            let approval_key = Subgame1::key_of(OPERATOR_APPROVALS_PREFIX, &(accounts.alice, accounts.bob));
+            assert_eq!(approval_key, Subgame1::key_of(OPERATOR_APPROVALS_PREFIX, &(accounts.alice, accounts.bob)));
+            assert_ne!(approval_key, Subgame1::key_of(OPERATOR_APPROVALS_PREFIX, &(accounts.bob, accounts.alice)));
+            assert_ne!(approval_key, Subgame1::key_of(BALANCES_PREFIX, &(accounts.alice, accounts.bob)));
+        }
+
+        #[ink::test]
+        fn approve_and_transfer_leaves_no_approval() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(10));
+            assert_eq!(subgame1.mint(accounts.alice, 2, 10), Ok(10));
+
+            assert_eq!(subgame1.approve_and_transfer(accounts.bob, vec![1, 2], vec![4, 6], Vec::new()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 4);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 6);
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let transfer_event = &emitted_events[emitted_events.len() - 2];
+            let decoded_event = <Event as Decode>::decode(&mut &transfer_event.data[..]).expect("Cannot decode event");
+            if let Event::TransferBatch(TransferBatch { operator, from, to, .. }) = decoded_event {
+                assert_eq!((operator, from, to), (accounts.bob, accounts.alice, accounts.bob));
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferBatch event")
+            }
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::ApprovalForAll(ApprovalForAll { account, operator, approved }) = decoded_event {
+                assert_eq!((account, operator, approved), (accounts.alice, accounts.bob, false));
+            } else {
+                panic!("encountered unexpected event kind: expected an ApprovalForAll event")
+            }
+
+            assert_eq!(
+                subgame1.approve_and_transfer(accounts.bob, vec![1], vec![7], Vec::new()),
+                Err(Error::InsufficientBalance { id: 1, available: 6, required: 7 })
+            );
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+            assert_eq!(subgame1.approve_and_transfer(accounts.bob, vec![1], vec![1, 2], Vec::new()), Err(Error::InvalidArrayLength(1, 2)));
+            assert_eq!(
+                subgame1.approve_and_transfer(AccountId::from(ZERO_ACCOUNT), vec![1], vec![1], Vec::new()),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(subgame1.approve_and_transfer(accounts.alice, vec![1], vec![1], Vec::new()), Err(Error::ApprovalForSelf));
+            assert_eq!(subgame1.operators_of(accounts.alice), Vec::<AccountId>::new());
+        }
+
+        #[ink::test]
+        fn approve_and_transfer_keeps_existing_approval() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(10));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+
+            assert_eq!(subgame1.approve_and_transfer(accounts.bob, vec![1], vec![4], Vec::new()), Ok(()));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), true);
+        }
+
+        #[ink::test]
+        fn approve_and_transfer_obeys_operator_restrictions() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(10));
+            assert_eq!(subgame1.set_approvals_paused(true), Ok(()));
+
+            assert_eq!(subgame1.approve_and_transfer(accounts.bob, vec![1], vec![4], Vec::new()), Err(Error::ApprovalsPaused));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+
+            assert_eq!(subgame1.set_approvals_paused(false), Ok(()));
+            assert_eq!(subgame1.approve_and_transfer(accounts.bob, vec![1], vec![4], Vec::new()), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 4);
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+            assert_eq!(subgame1.operators_of(accounts.alice), Vec::<AccountId>::new());
+        }
+
+        #[ink::test]
+        fn mint_and_burn_return_resulting_balance() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+
+            let minted = subgame1.mint(accounts.bob, 1, 5);
+            assert_eq!(minted, Ok(subgame1.balance_of(accounts.bob, 1)));
+            assert_eq!(minted, Ok(15));
+
+            let burned = subgame1.burn(accounts.bob, 1, 4);
+            assert_eq!(burned, Ok(subgame1.balance_of(accounts.bob, 1)));
+            assert_eq!(burned, Ok(11));
+
+            assert_eq!(subgame1.burn(accounts.bob, 1, 11), Ok(0));
+        }
+
+        #[ink::test]
+        fn paused_id_is_blocked_while_others_flow() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint(accounts.alice, 2, 100), Ok(100));
+
+            assert_eq!(subgame1.pause_id(1), Ok(()));
+            assert_eq!(subgame1.is_id_paused(1), true);
+            assert_eq!(subgame1.is_id_paused(2), false);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 10), Err(Error::TokenPaused));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, vec![2, 1], vec![10, 10]), Err(Error::TokenPaused));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Err(Error::TokenPaused));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 2, 10), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 10);
+            assert_eq!(subgame1.mint(accounts.alice, 2, 10), Ok(100));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.unpause_id(1), Err(Error::OnlyOwner));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.unpause_id(1), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 10), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 10);
+        }
+
+        #[ink::test]
+        fn total_burned_counts_only_burns() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 300), Ok(()));
+            assert_eq!(subgame1.total_burned(1), 0);
+
+            assert_eq!(subgame1.burn(accounts.bob, 1, 100), Ok(200));
+            assert_eq!(subgame1.burn(accounts.alice, 1, 50), Ok(650));
+            assert_eq!(subgame1.total_burned(1), 150);
+            assert_eq!(subgame1.total_burned(2), 0);
+
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(750));
+            assert_eq!(subgame1.total_burned(1), 150);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 950);
+        }
+
+        #[ink::test]
+        fn error_codes_are_stable() {
+            let codes = [
+                (Error::InsufficientBalance { id: 1, available: 0, required: 1 }, 0),
+                (Error::ApprovalForSelf, 1),
+                (Error::InvalidArrayLength(1, 2), 2),
+                (Error::CannotFetchValue, 3),
+                (Error::OnlyCreator, 4),
+                (Error::NotApproved, 5),
+                (Error::OnlyOwner, 6),
+                (Error::AccountFrozen, 7),
+                (Error::TransfersLockedUntilReveal, 8),
+                (Error::MaxSupplyExceeded, 9),
+                (Error::SupplyLocked, 10),
+                (Error::PermitExpired, 11),
+                (Error::NonceAlreadyUsed, 12),
+                (Error::InvalidNonce, 13),
+                (Error::InvalidSignature, 14),
+                (Error::WalletMintLimitExceeded, 15),
+                (Error::TokensLocked, 16),
+                (Error::NotApprovedMarketplace, 17),
+                (Error::InvalidFee, 18),
+                (Error::InsufficientFee, 19),
+                (Error::NativeTransferFailed, 20),
+                (Error::ZeroValue, 21),
+                (Error::AllowanceOverflow, 22),
+                (Error::NoWrapper, 23),
+                (Error::WrapperCallFailed, 24),
+                (Error::InvalidSnapshot, 25),
+                (Error::NotNftType, 26),
+                (Error::InvalidNftMint, 27),
+                (Error::InvalidRange, 28),
+                (Error::TransferToSelfContract, 29),
+                (Error::OperatorNotAllowed, 30),
+                (Error::ClaimCapExceeded, 31),
+                (Error::UriAlreadySet, 32),
+                (Error::VoucherAlreadyRedeemed, 33),
+                (Error::InvalidVoucherSignature, 34),
+                (Error::InsufficientPayment, 35),
+                (Error::BatchTooLarge, 36),
+                (Error::TransferCooldown, 37),
+                (Error::NotPendingOwner, 38),
+                (Error::TokenPaused, 39),
+                (Error::TooManyOperators, 40),
+                (Error::ApprovalsPaused, 41),
+                (Error::MintingFinalized, 42),
+                (Error::EmptyBatch, 43),
+                (Error::RoyaltyNotEnforced, 44),
+                (Error::SupplyUnderflow, 45),
+                (Error::ReservedId, 46),
+                (Error::SupplyOverflow, 47),
+                (Error::FeeOverflow, 48),
+                (Error::TokenNotFound, 49),
+                (Error::InsufficientAllowance, 50),
+                (Error::TokenExists, 51),
+                (Error::NotOwner, 52),
+            ];
+            for (error, code) in codes.iter() {
+                assert_eq!(error.encode()[0], *code, "{:?} changed its error code", error);
+            }
+        }
+
+        #[ink::test]
+        fn operators_per_account_are_capped() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let operators = (0..MAX_OPERATORS_PER_ACCOUNT)
+                .map(|i| AccountId::from([0x10 + i as u8; 32]))
+                .collect::<Vec<_>>();
+            let one_too_many = AccountId::from([0xff; 32]);
+
+            assert_eq!(subgame1.set_approval_for_all_batch(operators[..MAX_OPERATORS_PER_ACCOUNT - 1].to_vec(), true), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all(operators[MAX_OPERATORS_PER_ACCOUNT - 1], true), Ok(false));
+            assert_eq!(subgame1.operators_of(accounts.alice).len(), MAX_OPERATORS_PER_ACCOUNT);
+
+            assert_eq!(subgame1.set_approval_for_all(one_too_many, true), Err(Error::TooManyOperators));
+            assert_eq!(subgame1.set_approval_for_all_batch(vec![operators[0], one_too_many], true), Err(Error::TooManyOperators));
+            assert_eq!(subgame1.set_approval_for_all(operators[0], true), Ok(true));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, one_too_many), false);
+
+            assert_eq!(subgame1.revoke_all_operators(), Ok(()));
+            assert_eq!(subgame1.operators_of(accounts.alice), Vec::<AccountId>::new());
+            assert_eq!(subgame1.set_approval_for_all(one_too_many, true), Ok(false));
+        }
+
+        #[ink::test]
+        fn last_active_advances_on_activity() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let start = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.last_active_of(accounts.alice), None);
+
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.last_active_of(accounts.alice), Some(start));
+            assert_eq!(subgame1.last_active_of(accounts.bob), None);
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            let now = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            assert!(now > start);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 10), Ok(()));
+            assert_eq!(subgame1.last_active_of(accounts.alice), Some(now));
+            assert_eq!(subgame1.last_active_of(accounts.bob), Some(now));
+
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            let later = ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+                .expect("Cannot get block timestamp");
+            assert_eq!(subgame1.burn(accounts.bob, 1, 5), Ok(5));
+            assert_eq!(subgame1.last_active_of(accounts.bob), Some(later));
+            assert_eq!(subgame1.last_active_of(accounts.alice), Some(now));
+        }
+
+        #[ink::test]
+        fn distribute_sends_varied_rewards() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint(accounts.alice, 2, 100), Ok(100));
+
+            assert_eq!(
+                subgame1.distribute(accounts.alice, vec![accounts.bob, accounts.charlie, accounts.django], vec![1, 2, 1], vec![10, 20, 30]),
+                Ok(())
+            );
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 10);
+            assert_eq!(subgame1.balance_of(accounts.charlie, 2), 20);
+            assert_eq!(subgame1.balance_of(accounts.django, 1), 30);
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 60);
+            assert_eq!(subgame1.balance_of(accounts.alice, 2), 80);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::BatchSequence(BatchSequence { seq, batch_len, .. }) = decoded_event {
+                assert_eq!((seq, batch_len), (2, 3));
+            } else {
+                panic!("encountered unexpected event kind: expected a BatchSequence event")
+            }
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.distribute(accounts.alice, vec![accounts.bob], vec![1], vec![1]), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn distribute_rejects_bad_input() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+
+            assert_eq!(
+                subgame1.distribute(accounts.alice, vec![accounts.bob, accounts.charlie], vec![1], vec![10]),
+                Err(Error::InvalidArrayLength(2, 1))
+            );
+            assert_eq!(
+                subgame1.distribute(accounts.alice, vec![accounts.bob], vec![1], vec![10, 20]),
+                Err(Error::InvalidArrayLength(1, 2))
+            );
+            assert_eq!(
+                subgame1.distribute(accounts.alice, vec![accounts.bob, AccountId::from(ZERO_ACCOUNT)], vec![1, 1], vec![10, 10]),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(
+                subgame1.distribute(accounts.alice, vec![accounts.bob, accounts.charlie], vec![1, 1], vec![60, 50]),
+                Err(Error::InsufficientBalance { id: 1, available: 100, required: 110 })
+            );
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 100);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+        }
+
+        #[ink::test]
+        fn distribute_with_frozen_recipient_moves_nothing() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.freeze(accounts.charlie), Ok(()));
+
+            assert_eq!(
+                subgame1.distribute(accounts.alice, vec![accounts.bob, accounts.charlie, accounts.django], vec![1, 1, 1], vec![10, 20, 30]),
+                Err(Error::AccountFrozen)
+            );
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 100);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 0);
+            assert_eq!(subgame1.balance_of(accounts.django, 1), 0);
+        }
+
+        #[ink::test]
+        fn holders_of_paginates_and_drops_empty_holders() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 20), Ok(20));
+            assert_eq!(subgame1.mint(accounts.charlie, 1, 30), Ok(30));
+
+            assert_eq!(subgame1.holders_of(1, 0, 2), vec![(accounts.alice, 100), (accounts.bob, 20)]);
+            assert_eq!(subgame1.holders_of(1, 2, 2), vec![(accounts.charlie, 30)]);
+            assert_eq!(subgame1.holders_of(1, 3, 2), Vec::new());
+            assert_eq!(subgame1.holders_of(1, 0, 0), Vec::new());
+            assert_eq!(subgame1.holders_of(2, 0, 10), Vec::new());
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.django, 1, 20), Ok(()));
+            assert_eq!(
+                subgame1.holders_of(1, 0, 10),
+                vec![(accounts.alice, 100), (accounts.charlie, 30), (accounts.django, 20)]
+            );
+        }
+
+        #[ink::test]
+        fn paused_approvals_block_only_new_approvals() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_approvals_paused(true), Err(Error::OnlyOwner));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.set_approvals_paused(true), Ok(()));
+            assert_eq!(subgame1.approvals_paused(), true);
+            assert_eq!(subgame1.set_approval_for_all(accounts.charlie, true), Err(Error::ApprovalsPaused));
+            assert_eq!(subgame1.set_approval_for_all_batch(vec![accounts.charlie], true), Err(Error::ApprovalsPaused));
+            assert_eq!(subgame1.set_approval_for_all_until(accounts.charlie, true, Timestamp::MAX), Err(Error::ApprovalsPaused));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.charlie), false);
+            assert_eq!(subgame1.increase_allowance(accounts.charlie, 1, 10), Err(Error::ApprovalsPaused));
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.charlie, Some(1), 10), Err(PSP37Error::Custom(String::from("ApprovalsPaused"))));
+            assert_eq!(PSP37::allowance(&subgame1, accounts.alice, accounts.charlie, Some(1)), 0);
+            assert_eq!(PSP37::approve(&mut subgame1, accounts.charlie, Some(1), 0), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 10), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 10);
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, false), Ok(true));
+            assert_eq!(subgame1.set_approvals_paused(false), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all(accounts.charlie, true), Ok(false));
+        }
+
+        #[ink::test]
+        fn uri_precedence_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_base_uri(b"https://game.example/{id}".to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create(Vec::new()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1), Ok(1));
+            assert_eq!(subgame1.mint(accounts.alice, 2, 1), Ok(1));
+
+            // Never-minted ids report the fallback, which is empty until set.
+            assert_eq!(subgame1.uri(3), Vec::<u8>::new());
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_fallback_uri(b"https://game.example/unknown".to_vec()), Err(Error::OnlyOwner));
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.set_fallback_uri(b"https://game.example/unknown".to_vec()), Ok(()));
+            assert_eq!(subgame1.uri(3), b"https://game.example/unknown".to_vec());
+
+            // A token's own uri wins over the template.
+            assert_eq!(subgame1.uri(1), [0x01].to_vec());
+
+            // Minted ids without their own uri expand the template, even once burned out.
+            let expanded = subgame1.uri(2);
+            assert!(expanded.starts_with(b"https://game.example/"));
+            assert!(expanded.ends_with(b"0002"));
+            assert_eq!(subgame1.burn(accounts.alice, 2, 1), Ok(0));
+            assert_eq!(subgame1.uri(2), expanded);
+        }
+
+        #[ink::test]
+        fn transfer_and_revoke_revokes_operator() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.transfer_and_revoke(accounts.alice, accounts.charlie, 1, 10), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 10);
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let decoded_events = emitted_events.iter().rev().take(2)
+                .map(|event| <Event as Decode>::decode(&mut &event.data[..]).expect("Cannot decode event"))
+                .collect::<Vec<_>>();
+            match (&decoded_events[1], &decoded_events[0]) {
+                (Event::TransferSingle(TransferSingle { operator, to, .. }), Event::ApprovalForAll(ApprovalForAll { account, operator: revoked, approved })) => {
+                    assert_eq!((*operator, *to), (accounts.bob, accounts.charlie));
+                    assert_eq!((*account, *revoked, *approved), (accounts.alice, accounts.bob, false));
+                }
+                _ => panic!("encountered unexpected event kinds: expected TransferSingle then ApprovalForAll"),
+            }
+
+            assert_eq!(subgame1.transfer_and_revoke(accounts.alice, accounts.charlie, 1, 10), Err(Error::NotApproved));
+        }
+
+        #[ink::test]
+        fn transfer_and_revoke_by_owner_only_transfers() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+
+            assert_eq!(subgame1.transfer_and_revoke(accounts.alice, accounts.charlie, 1, 10), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 10);
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), true);
+        }
+
+        #[ink::test]
+        fn remaining_supply_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x03].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_max_supply(1, 100), Ok(()));
+            assert_eq!(subgame1.set_max_supply(2, 50), Ok(()));
+
+            assert_eq!(subgame1.remaining_supply(1), Some(100));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 40), Ok(40));
+            assert_eq!(subgame1.remaining_supply(1), Some(60));
+
+            assert_eq!(subgame1.mint(accounts.alice, 2, 50), Ok(50));
+            assert_eq!(subgame1.remaining_supply(2), Some(0));
+
+            assert_eq!(subgame1.mint(accounts.alice, 3, 50), Ok(50));
+            assert_eq!(subgame1.remaining_supply(3), None);
+        }
+
+        #[ink::test]
+        fn selectors_match_message_names() {
+            let selectors = [
+                (&b"balance_of"[..], crate::selectors::BALANCE_OF),
+                (&b"safe_transfer_from"[..], crate::selectors::SAFE_TRANSFER_FROM),
+                (&b"mint"[..], crate::selectors::MINT),
+                (&b"burn"[..], crate::selectors::BURN),
+                (&b"set_approval_for_all"[..], crate::selectors::SET_APPROVAL_FOR_ALL),
+            ];
+            for (name, selector) in selectors.iter() {
+                let mut hash = [0u8; 32];
+                ink_env::hash_bytes::<Blake2x256>(name, &mut hash);
+                assert_eq!(hash[..4], selector[..]);
+            }
+        }
+
+        #[ink::test]
+        fn mint_fee_is_forwarded_to_fee_recipient() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_transfer_fee(0, Some(accounts.charlie)), Ok(()));
+            assert_eq!(subgame1.set_mint_fee(10), Ok(()));
+            assert_eq!(subgame1.mint_fee(), 10);
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+            let charlie_before = native_balance_of(accounts.charlie);
+            let alice_before = native_balance_of(accounts.alice);
+
+            set_sender_with_value(accounts.alice, 50);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 5), Ok(5));
+            assert_eq!(native_balance_of(accounts.charlie), charlie_before + 50);
+            assert_eq!(native_balance_of(accounts.alice), alice_before);
+        }
+
+        #[ink::test]
+        fn mint_fee_refunds_excess() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_transfer_fee(0, Some(accounts.charlie)), Ok(()));
+            assert_eq!(subgame1.set_mint_fee(10), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+            let charlie_before = native_balance_of(accounts.charlie);
+            let alice_before = native_balance_of(accounts.alice);
+
+            set_sender_with_value(accounts.alice, 80);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 5), Ok(5));
+            assert_eq!(native_balance_of(accounts.charlie), charlie_before + 50);
+            assert_eq!(native_balance_of(accounts.alice), alice_before + 30);
+        }
+
+        #[ink::test]
+        fn mint_fee_underpayment_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_mint_fee(10), Ok(()));
+
+            set_sender_with_value(accounts.alice, 49);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 5), Err(Error::InsufficientFee));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+        }
+
+        #[ink::test]
+        fn mint_batch_and_claim_charge_mint_fee() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_transfer_fee(0, Some(accounts.charlie)), Ok(()));
+            assert_eq!(subgame1.set_claim_cap(accounts.bob, 1, 10), Ok(()));
+            assert_eq!(subgame1.set_mint_fee(10), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+            let charlie_before = native_balance_of(accounts.charlie);
+
+            set_sender_with_value(accounts.alice, 49);
+            assert_eq!(subgame1.mint_batch(accounts.bob, vec![1, 2], vec![2, 3]), Err(Error::InsufficientFee));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 0);
+
+            set_sender_with_value(accounts.alice, 50);
+            assert_eq!(subgame1.mint_batch(accounts.bob, vec![1, 2], vec![2, 3]), Ok(()));
+            assert_eq!(native_balance_of(accounts.charlie), charlie_before + 50);
+
+            set_sender_with_value(accounts.bob, 39);
+            assert_eq!(subgame1.claim(1, 4), Err(Error::InsufficientFee));
+            assert_eq!(subgame1.claim_cap_of(accounts.bob, 1), 10);
+
+            set_sender_with_value(accounts.bob, 40);
+            assert_eq!(subgame1.claim(1, 4), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 6);
+            assert_eq!(native_balance_of(accounts.charlie), charlie_before + 90);
+        }
+
+        #[ink::test]
+        fn failed_mint_takes_no_fee() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.set_transfer_fee(0, Some(accounts.charlie)), Ok(()));
+            assert_eq!(subgame1.set_max_supply(1, 5), Ok(()));
+            assert_eq!(subgame1.set_mint_fee(10), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+            let charlie_before = native_balance_of(accounts.charlie);
+
+            set_sender_with_value(accounts.alice, 60);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 6), Err(Error::MaxSupplyExceeded));
+            assert_eq!(native_balance_of(accounts.charlie), charlie_before);
+        }
+
+        #[ink::test]
+        fn mint_without_fee_refunds_payment() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+            let alice_before = native_balance_of(accounts.alice);
+
+            set_sender_with_value(accounts.alice, 30);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 5), Ok(5));
+            assert_eq!(native_balance_of(accounts.alice), alice_before + 30);
+        }
+
+        #[ink::test]
+        fn only_owner_can_set_mint_fee() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_mint_fee(10), Err(Error::OnlyOwner));
+            assert_eq!(subgame1.mint_fee(), 0);
+        }
+
+        #[ink::test]
+        fn finalize_minting_blocks_mints_permanently() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+            assert_eq!(subgame1.set_claim_cap(accounts.bob, 1, 5), Ok(()));
+
+            assert_eq!(subgame1.finalize_minting(), Ok(()));
+            assert!(subgame1.minting_finalized());
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::MintingFinalized(MintingFinalized { operator }) = decoded_event {
+                assert_eq!(operator, accounts.alice);
+            } else {
+                panic!("encountered unexpected event kind: expected a MintingFinalized event")
+            }
+
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Err(Error::MintingFinalized));
+            assert_eq!(subgame1.mint_batch(accounts.bob, vec![1], vec![10]), Err(Error::MintingFinalized));
+            assert_eq!(subgame1.create([0x02].to_vec()), Err(Error::MintingFinalized));
+            assert_eq!(subgame1.create_token(10, accounts.bob), Err(Error::MintingFinalized));
+            assert_eq!(subgame1.create_nft_type([0x03].to_vec()), Err(Error::MintingFinalized));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.claim(1, 5), Err(Error::MintingFinalized));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 10);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 10);
+
+            // Transfers and burns keep working.
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 4), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.charlie, 1), 4);
+        }
+
+        #[ink::test]
+        fn only_owner_can_finalize_minting() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.finalize_minting(), Err(Error::OnlyOwner));
+            assert!(!subgame1.minting_finalized());
+        }
+
+        #[ink::test]
+        fn decimals_work() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.decimals_of(1), 0);
+
+            assert_eq!(subgame1.set_decimals(1, 18), Ok(()));
+            assert_eq!(subgame1.decimals_of(1), 18);
+            assert_eq!(subgame1.decimals_of(2), 0);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::BatchMetadataUpdate(BatchMetadataUpdate { from_id, to_id }) = decoded_event {
+                assert_eq!(from_id, 1);
+                assert_eq!(to_id, 1);
+            } else {
+                panic!("encountered unexpected event kind: expected a BatchMetadataUpdate event")
+            }
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_decimals(1, 6), Err(Error::OnlyOwner));
+            assert_eq!(subgame1.decimals_of(1), 18);
+        }
+
+        #[ink::test]
+        fn swap_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(10));
+            assert_eq!(subgame1.mint(accounts.bob, 2, 10), Ok(10));
+
+            assert_eq!(subgame1.swap(accounts.bob, 1, 4, 2, 3), Err(Error::NotApproved));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_approval_for_all(accounts.alice, true), Ok(()));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.swap(accounts.bob, 1, 4, 2, 3), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 6);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 4);
+            assert_eq!(subgame1.balance_of(accounts.alice, 2), 3);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 7);
+        }
+
+        #[ink::test]
+        fn swap_without_counterparty_balance_moves_nothing() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(10));
+            assert_eq!(subgame1.mint(accounts.bob, 2, 10), Ok(10));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_approval_for_all(accounts.alice, true), Ok(()));
+
+            set_sender(accounts.alice);
+            assert_eq!(
+                subgame1.swap(accounts.bob, 1, 4, 2, 11),
+                Err(Error::InsufficientBalance { id: 2, available: 10, required: 11 })
+            );
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 10);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 10);
+        }
+
+        #[ink::test]
+        fn stats_track_holders() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.stats(), (0, 0, false));
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
+            assert_eq!(subgame1.mint(accounts.bob, 2, 10), Ok(10));
+            assert_eq!(subgame1.stats(), (2, 1, false));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 1, 10), Ok(()));
+            assert_eq!(subgame1.stats(), (2, 2, false));
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, accounts.charlie, 2, 10), Ok(()));
+            assert_eq!(subgame1.stats(), (2, 1, false));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.burn(accounts.charlie, 1, 10), Ok(0));
+            assert_eq!(subgame1.stats(), (2, 1, false));
+            assert_eq!(subgame1.burn(accounts.charlie, 2, 10), Ok(0));
+            assert_eq!(subgame1.stats(), (2, 0, false));
+
+            assert_eq!(subgame1.set_approvals_paused(true), Ok(()));
+            assert_eq!(subgame1.stats(), (2, 0, false));
+            assert_eq!(subgame1.pause_id(2), Ok(()));
+            assert_eq!(subgame1.stats(), (2, 0, true));
+            assert_eq!(subgame1.unpause_id(2), Ok(()));
+            assert_eq!(subgame1.stats(), (2, 0, false));
+        }
+
+        #[ink::test]
+        fn empty_batches_are_rejected() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            let events_before = ink_env::test::recorded_events().count();
+
+            assert_eq!(subgame1.mint_batch(accounts.bob, vec![], vec![]), Err(Error::EmptyBatch));
+            assert_eq!(subgame1.burn_batch(accounts.bob, vec![], vec![]), Err(Error::EmptyBatch));
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, vec![], vec![]), Err(Error::EmptyBatch));
+            assert_eq!(ink_env::test::recorded_events().count(), events_before);
+        }
+
+        #[ink::test]
+        fn enforced_royalties_block_unlisted_operators() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 10), Ok(10));
+            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(false));
+            assert_eq!(subgame1.set_approval_for_all(accounts.charlie, true), Ok(false));
+            assert_eq!(subgame1.set_operator_allowed(accounts.bob, true), Ok(()));
+            assert_eq!(subgame1.set_enforce_royalties(true), Ok(()));
+            assert!(subgame1.enforces_royalties());
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.django, 1, 2), Ok(()));
+
+            set_sender(accounts.charlie);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.django, 1, 2), Err(Error::RoyaltyNotEnforced));
+            assert_eq!(
+                subgame1.safe_batch_transfer_from(accounts.alice, accounts.django, vec![1], vec![2]),
+                Err(Error::RoyaltyNotEnforced)
+            );
+            assert_eq!(subgame1.balance_of(accounts.django, 1), 2);
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.django, 1, 3), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 5);
+            assert_eq!(subgame1.balance_of(accounts.django, 1), 5);
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.set_enforce_royalties(false), Err(Error::OnlyOwner));
+        }
+
+        #[ink::test]
+        fn invalidated_nonce_rejects_signed_permit() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let owner = AccountId::from(SIGNER);
+            let operator = AccountId::from([0x02; 32]);
+            assert_eq!(subgame1.nonce_of(owner), 0);
 
-                self.add_token_to(&to, &id, value)?;
+            assert_eq!(subgame1.invalidate_nonces(owner), Ok(()));
+            assert_eq!(subgame1.nonce_of(owner), 1);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::NoncesInvalidated(NoncesInvalidated { account, nonce }) = decoded_event {
+                assert_eq!(account, owner);
+                assert_eq!(nonce, 1);
+            } else {
+                panic!("encountered unexpected event kind: expected a NoncesInvalidated event")
             }
 
-            self.env().emit_event(TransferBatch {
-                operator: caller,
-                from: AccountId::from([0x0; 32]),
-                to,
-                ids,
-                values,
-            });
+            assert_eq!(subgame1.permit(owner, operator, true, 0, Timestamp::MAX, PERMIT_SIGNATURE), Err(Error::NonceAlreadyUsed));
+            assert_eq!(subgame1.is_approved_for_all(owner, operator), false);
 
-            Ok(())
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.invalidate_nonces(owner), Err(Error::OnlyOwner));
+            assert_eq!(subgame1.nonce_of(owner), 1);
         }
 
-        /// Destroys `value` tokens of token type `id` from `account`
-        #[ink(message)]
-        pub fn burn(&mut self, from: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error> {
-            let caller = self.env().caller();
+        #[ink::test]
+        fn mint_batch_with_uris_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let uris = vec![b"ipfs://a".to_vec(), b"ipfs://b".to_vec(), b"ipfs://c".to_vec()];
+            assert_eq!(subgame1.mint_batch_with_uris(accounts.bob, vec![1, 2, 3], vec![1, 1, 1], uris.clone()), Ok(()));
 
-            if !self.is_creator(caller, id) {
-                return Err(Error::OnlyCreator);
+            for (id, uri) in [1, 2, 3].iter().zip(uris.iter()) {
+                assert_eq!(subgame1.balance_of(accounts.bob, *id), 1);
+                assert_eq!(subgame1.uri(*id), *uri);
             }
 
-            if from == AccountId::from([0x0; 32]) {
-                return Err(Error::NotApproved);
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 4);
+            let decoded_event = <Event as Decode>::decode(&mut &emitted_events[0].data[..]).expect("Cannot decode event");
+            if let Event::TransferBatch(TransferBatch { to, ids, values, .. }) = decoded_event {
+                assert_eq!(to, accounts.bob);
+                assert_eq!(ids, vec![1, 2, 3]);
+                assert_eq!(values, vec![1, 1, 1]);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferBatch event")
+            }
+            let decoded_event = <Event as Decode>::decode(&mut &emitted_events[3].data[..]).expect("Cannot decode event");
+            if let Event::Uri(Uri { value, id }) = decoded_event {
+                assert_eq!(value, b"ipfs://c".to_vec());
+                assert_eq!(id, 3);
+            } else {
+                panic!("encountered unexpected event kind: expected a Uri event")
             }
+        }
 
-            self.remove_token_from(&from, &id, value)?;
+        #[ink::test]
+        fn mint_batch_with_uris_length_mismatch_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(
+                subgame1.mint_batch_with_uris(accounts.bob, vec![1, 2], vec![1, 1], vec![b"ipfs://a".to_vec()]),
+                Err(Error::InvalidArrayLength(2, 1))
+            );
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+        }
 
-            self.env().emit_event(TransferSingle {
-                operator: caller,
-                from,
-                to: AccountId::from([0x0; 32]),
-                id,
-                value,
-            });
+        #[ink::test]
+        fn mint_batch_with_uris_rejects_assigned_uri() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, 2, 1, b"ipfs://taken".to_vec()), Ok(()));
 
-            Ok(())
+            let uris = vec![b"ipfs://a".to_vec(), b"ipfs://b".to_vec()];
+            assert_eq!(subgame1.mint_batch_with_uris(accounts.bob, vec![1, 2], vec![1, 1], uris), Err(Error::UriAlreadySet));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+            assert_eq!(subgame1.uri(2), b"ipfs://taken".to_vec());
         }
 
-        #[ink(message)]
-        pub fn burn_batch(&mut self, from: AccountId, ids: Vec<TokenId>, values: Vec<TokenBalance>) -> Result<(), Error> {
-            let caller = self.env().caller();
-
-            if from == AccountId::from([0x0; 32]) {
-                return Err(Error::NotApproved);
-            }
+        #[ink::test]
+        fn burn_beyond_tracked_supply_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 10), Ok(10));
 
-            if ids.len() != values.len() {
-                return Err(Error::InvalidArrayLength);
-            }
+            // Simulate counters that drifted out of sync with the balances.
+            subgame1.total_supply.insert(1, 5);
 
-            for i in 0..ids.len() {
-                let id = ids[i];
-                let value = values[i];
+            assert_eq!(subgame1.burn(accounts.bob, 1, 6), Err(Error::SupplyUnderflow));
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 5);
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 10);
+        }
 
-                if !self.is_creator(caller, id) {
-                    return Err(Error::OnlyCreator);
-                }
+        #[ink::test]
+        fn wrapped_native_cannot_be_minted() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_authorized_minter(accounts.django, true), Ok(()));
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, WRAPPED_NATIVE_ID, 10, [0x01].to_vec()), Err(Error::ReservedId));
 
-                self.remove_token_from(&from, &id, value)?;
-            }
+            set_sender(accounts.django);
+            assert_eq!(subgame1.mint(accounts.bob, WRAPPED_NATIVE_ID, 10), Err(Error::OnlyCreator));
+            assert_eq!(subgame1.mint_batch(accounts.bob, vec![WRAPPED_NATIVE_ID], vec![10]), Err(Error::OnlyCreator));
+            assert_eq!(subgame1.balance_of(accounts.bob, WRAPPED_NATIVE_ID), 0);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(WRAPPED_NATIVE_ID)), 0);
+        }
 
-            self.env().emit_event(TransferBatch {
-                operator: caller,
-                from,
-                to: AccountId::from([0x0; 32]),
-                ids,
-                values,
-            });
+        #[ink::test]
+        fn deposit_works_after_minting_finalized() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.finalize_minting(), Ok(()));
 
-            Ok(())
+            set_sender_with_value(accounts.bob, 500);
+            assert_eq!(subgame1.deposit(), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, WRAPPED_NATIVE_ID), 500);
         }
 
-        fn transfer_token_from(&mut self, from: &AccountId, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
-            self.remove_token_from(from, id, value)?;
-            self.add_token_to(to, id, value)?;
+        #[ink::test]
+        fn wrap_obeys_transfer_restrictions() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let wrapper = AccountId::from([0x10; 32]);
+            let mut subgame1 = Subgame1::with_wrapper(wrapper, 1);
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1000));
 
-            Ok(())
-        }
+            assert_eq!(subgame1.freeze(accounts.bob), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.wrap(1, 100), Err(Error::AccountFrozen));
 
-        fn add_token_to(&mut self, to: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
-            let to_balance = self.balance_of_or_zero(&to, &id);
-            self.balances.insert((*to, *id), to_balance + value);
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.unfreeze(accounts.bob), Ok(()));
+            assert_eq!(subgame1.pause_id(1), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.wrap(1, 100), Err(Error::TokenPaused));
 
-            Ok(())
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 1000);
+            assert!(mock_erc20::calls().is_empty());
         }
 
-        fn remove_token_from(&mut self, from: &AccountId, id: &TokenId, value: TokenBalance) -> Result<(), Error> {
-            let from_balance = self.balance_of_or_zero(from, id);
-            if from_balance < value {
-                return Err(Error::InsufficientBalance);
-            }
+        #[ink::test]
+        fn unwrap_obeys_pause_but_not_finalize() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let wrapper = AccountId::from([0x10; 32]);
+            let mut subgame1 = Subgame1::with_wrapper(wrapper, 1);
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1000));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.wrap(1, 400), Ok(()));
 
-            self.balances.insert((*from, *id), from_balance - value);
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.pause_id(1), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.unwrap(1, 100), Err(Error::TokenPaused));
 
-            Ok(())
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.unpause_id(1), Ok(()));
+            assert_eq!(subgame1.finalize_minting(), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.unwrap(1, 100), Ok(()));
+
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 700);
+            assert_eq!(mock_erc20::balance_of(accounts.bob), 300);
         }
 
-        fn approved_or_owner(&self, account: AccountId, caller: AccountId) -> bool {
-            account != AccountId::from([0x0; 32])
-                && (account == caller || self.approved_for_all(&account, &caller))
+        #[ink::test]
+        fn wrapper_call_observes_completed_writes() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let wrapper = AccountId::from([0x10; 32]);
+            let mut subgame1 = Subgame1::with_wrapper(wrapper, 1);
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1000));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.wrap(1, 400), Ok(()));
+            assert_eq!(subgame1.unwrap(1, 150), Ok(()));
+            assert_eq!(subgame1.unwrap(1, 251), Err(Error::WrapperCallFailed));
+
+            // The burn of `wrap` and the re-issue of `unwrap` are already visible while the
+            // wrapper runs; the failed unwrap is rolled back afterwards.
+            assert_eq!(mock_erc20::observed(), [(600, 600), (750, 750), (1001, 1001)].to_vec());
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 750);
+            assert_eq!(PSP37::total_supply(&subgame1, Some(1)), 750);
         }
 
-        fn is_creator(&self, caller: AccountId, id: TokenId) -> bool {
-            self.token_creator.get(&id) == Some(&caller)
+        #[ink::test]
+        fn transfer_fee_events_report_net_amount() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint_batch(accounts.alice, vec![1, 2], vec![1000, 1000]), Ok(()));
+            assert_eq!(subgame1.set_transfer_fee(250, Some(accounts.django)), Ok(()));
+            let events_before = ink_env::test::recorded_events().count();
+
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 400), Ok(()));
+            let emitted_events = ink_env::test::recorded_events().skip(events_before).collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+            let decoded_event = <Event as Decode>::decode(&mut &emitted_events[0].data[..]).expect("Cannot decode event");
+            if let Event::TransferSingle(TransferSingle { to, value, .. }) = decoded_event {
+                assert_eq!(to, accounts.django);
+                assert_eq!(value, 10);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferSingle event")
+            }
+            let decoded_event = <Event as Decode>::decode(&mut &emitted_events[1].data[..]).expect("Cannot decode event");
+            if let Event::TransferSingle(TransferSingle { to, value, .. }) = decoded_event {
+                assert_eq!(to, accounts.bob);
+                assert_eq!(value, 390);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferSingle event")
+            }
+
+            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, vec![1, 2], vec![200, 400]), Ok(()));
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let last_event = emitted_events.last().expect("No events emitted");
+            let decoded_event = <Event as Decode>::decode(&mut &last_event.data[..]).expect("Cannot decode event");
+            if let Event::TransferBatch(TransferBatch { to, ids, values, .. }) = decoded_event {
+                assert_eq!(to, accounts.bob);
+                assert_eq!(ids, vec![1, 2]);
+                assert_eq!(values, vec![195, 390]);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferBatch event")
+            }
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 585);
+            assert_eq!(subgame1.balance_of(accounts.bob, 2), 390);
         }
 
-        fn balance_of_or_zero(&self, account: &AccountId, id: &TokenId) -> TokenBalance {
-            *self.balances.get(&(*account, *id)).unwrap_or(&0)
+        #[ink::test]
+        fn transfer_fee_overflow_fails() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, TokenBalance::MAX), Ok(TokenBalance::MAX));
+            assert_eq!(subgame1.set_transfer_fee(250, Some(accounts.django)), Ok(()));
+
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, TokenBalance::MAX), Err(Error::FeeOverflow));
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), TokenBalance::MAX);
         }
 
-        fn approved_for_all(&self, account: &AccountId, operator: &AccountId) -> bool {
-            *self.operator_approvals.get(&(*account, *operator)).unwrap_or(&false)
+        #[ink::test]
+        fn market_transfer_refunds_excess_payment() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_treasury(accounts.frank), Ok(()));
+            assert_eq!(subgame1.set_marketplace(accounts.django, Some(250)), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, true), Ok(false));
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+            let treasury_balance = native_balance_of(accounts.frank);
+            let marketplace_balance = native_balance_of(accounts.django);
+
+            set_sender_with_value(accounts.django, 400);
+            assert_eq!(subgame1.market_transfer(accounts.alice, accounts.bob, 1, 100, 10_000), Ok(()));
+            assert_eq!(native_balance_of(accounts.frank), treasury_balance + 250);
+            assert_eq!(native_balance_of(accounts.django), marketplace_balance + 150);
+
+            assert_eq!(subgame1.market_transfer(accounts.alice, accounts.bob, 1, 100, Balance::MAX), Err(Error::FeeOverflow));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 100);
         }
 
-        /// Returns true if token `id` exists or false if it does not.
-        fn exists(&self, id: TokenId) -> bool {
-            self.token_creator.get(&id).is_some() && self.token_creator.contains_key(&id)
+        #[ink::test]
+        fn market_transfer_event_reports_net_amount() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_transfer_fee(1_000, Some(accounts.charlie)), Ok(()));
+            assert_eq!(subgame1.set_marketplace(accounts.django, Some(0)), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, true), Ok(false));
+
+            set_sender_with_value(accounts.django, 0);
+            assert_eq!(subgame1.market_transfer(accounts.alice, accounts.bob, 1, 100, 10_000), Ok(()));
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            let transfer_event = &emitted_events[emitted_events.len() - 2];
+            let decoded_event = <Event as Decode>::decode(&mut &transfer_event.data[..]).expect("Cannot decode event");
+            if let Event::TransferSingle(TransferSingle { to, value, .. }) = decoded_event {
+                assert_eq!(to, accounts.bob);
+                assert_eq!(value, 90);
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferSingle event")
+            }
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 90);
         }
 
-    }
+        #[ink::test]
+        fn mint_with_uri_rejects_reserved_ids() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            let nft_instance = NF_BIT | (1 << 64) | 1;
 
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
-        use ink_lang as ink;
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, nft_instance, 1, [0x01].to_vec()), Err(Error::ReservedId));
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, TokenId::MAX, 1, [0x01].to_vec()), Err(Error::ReservedId));
+            assert_eq!(
+                subgame1.mint_batch_with_uris(accounts.bob, vec![7, nft_instance], vec![1, 1], vec![[0x01].to_vec(), [0x02].to_vec()]),
+                Err(Error::ReservedId)
+            );
+            assert_eq!(subgame1.balance_of(accounts.bob, 7), 0);
+            assert_eq!(subgame1.balance_of(accounts.bob, nft_instance), 0);
+
+            assert_eq!(subgame1.mint_with_uri(accounts.bob, WRAPPED_NATIVE_ID - 1, 1, [0x01].to_vec()), Ok(()));
+            assert_eq!(subgame1.next_token_id, WRAPPED_NATIVE_ID);
+        }
 
         #[ink::test]
-        fn create_works() {
+        fn mint_locked_rejects_unregistered_ids() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             let mut subgame1 = Subgame1::new();
-            assert_eq!(subgame1.creator_of(1), None);
+            assert_eq!(subgame1.mint_locked(accounts.bob, 1, 300, 0), Err(Error::TokenNotFound));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+
             assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
-            assert_eq!(subgame1.creator_of(1), Some(accounts.alice));
+            assert_eq!(subgame1.mint_locked(accounts.bob, 1, 300, 0), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 300);
         }
 
         #[ink::test]
-        fn mint_works() {
+        fn claim_rejects_unregistered_ids() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            assert_eq!(subgame1.set_claim_cap(accounts.bob, 1, 5), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.claim(1, 5), Err(Error::TokenNotFound));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
+            assert_eq!(subgame1.claim_cap_of(accounts.bob, 1), 5);
+        }
+
+        #[ink::test]
+        fn market_transfer_rejects_zero_recipient() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             let mut subgame1 = Subgame1::new();
             assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
-            assert_eq!(subgame1.balance_of(accounts.alice, 1), 0);
-            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_marketplace(accounts.django, Some(0)), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, true), Ok(false));
+
+            set_sender(accounts.django);
+            assert_eq!(subgame1.market_transfer(accounts.alice, AccountId::from(ZERO_ACCOUNT), 1, 100, 10_000), Err(Error::NotApproved));
             assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
         }
 
         #[ink::test]
-        fn mint_batch_works() {
+        fn market_transfer_rejects_zero_value() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             let mut subgame1 = Subgame1::new();
             assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
-            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
-            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([0, 0].to_vec()));
-            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
-            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([1000, 1000].to_vec()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_marketplace(accounts.django, Some(0)), Ok(()));
+            assert_eq!(subgame1.set_approval_for_all(accounts.django, true), Ok(false));
+            let emitted = ink_env::test::recorded_events().count();
+
+            set_sender(accounts.django);
+            assert_eq!(subgame1.market_transfer(accounts.alice, accounts.bob, 1, 0, 10_000), Err(Error::ZeroValue));
+            assert_eq!(ink_env::test::recorded_events().count(), emitted);
         }
 
         #[ink::test]
-        fn burn_works() {
+        fn withdraw_obeys_freeze_and_pause() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let mut subgame1 = Subgame1::new();
+            ink_env::test::set_account_balance::<ink_env::DefaultEnvironment>(contract_id(), 1000)
+                .expect("Cannot set account balance");
+
+            set_sender_with_value(accounts.bob, 500);
+            assert_eq!(subgame1.deposit(), Ok(()));
+            let bob_balance = native_balance_of(accounts.bob);
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.freeze(accounts.bob), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.withdraw(200), Err(Error::AccountFrozen));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.unfreeze(accounts.bob), Ok(()));
+            assert_eq!(subgame1.pause_id(WRAPPED_NATIVE_ID), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.withdraw(200), Err(Error::TokenPaused));
+            assert_eq!(subgame1.balance_of(accounts.bob, WRAPPED_NATIVE_ID), 500);
+            assert_eq!(native_balance_of(accounts.bob), bob_balance);
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.unpause_id(WRAPPED_NATIVE_ID), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.withdraw(200), Ok(()));
+            assert_eq!(native_balance_of(accounts.bob), bob_balance + 200);
+        }
+
+        #[ink::test]
+        fn locked_tokens_keep_their_holder() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             let mut subgame1 = Subgame1::new();
             assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
-            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(()));
-            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
-            assert_eq!(subgame1.burn(accounts.alice, 1, 200), Ok(()));
-            assert_eq!(subgame1.balance_of(accounts.alice, 1), 800);
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.lock(1, 800), Ok(()));
+            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
+
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 0);
+            assert_eq!(subgame1.tokens_of(accounts.alice), [1]);
+            assert_eq!(subgame1.holders_of(1, 0, 10), [(accounts.alice, 800), (accounts.bob, 200)]);
+            assert_eq!(subgame1.stats().1, 2);
+
+            assert_eq!(subgame1.unlock(1, 800), Ok(()));
+            assert_eq!(subgame1.tokens_of(accounts.alice), [1]);
+            assert_eq!(subgame1.holders_of(1, 0, 10), [(accounts.alice, 800), (accounts.bob, 200)]);
+            assert_eq!(subgame1.stats().1, 2);
         }
 
         #[ink::test]
-        fn burn_batch_works() {
+        fn lock_and_unlock_obey_freeze_and_pause() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             let mut subgame1 = Subgame1::new();
             assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
-            assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
-            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
-            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([1000, 1000].to_vec()));
-            assert_eq!(subgame1.burn_batch(accounts.alice, [1, 2].to_vec(), [200, 200].to_vec()), Ok(()));
-            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([800, 800].to_vec()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.lock(1, 500), Ok(()));
+
+            assert_eq!(subgame1.freeze(accounts.alice), Ok(()));
+            assert_eq!(subgame1.lock(1, 100), Err(Error::AccountFrozen));
+            assert_eq!(subgame1.unlock(1, 100), Err(Error::AccountFrozen));
+            assert_eq!(subgame1.unfreeze(accounts.alice), Ok(()));
+
+            assert_eq!(subgame1.pause_id(1), Ok(()));
+            assert_eq!(subgame1.lock(1, 100), Err(Error::TokenPaused));
+            assert_eq!(subgame1.unlock(1, 100), Err(Error::TokenPaused));
+            assert_eq!(subgame1.locked_balance_of(accounts.alice, 1), 500);
+            assert_eq!(subgame1.balance_of(accounts.alice, 1), 500);
         }
 
         #[ink::test]
-        fn safe_transfer_from_works() {
+        fn recover_obeys_freeze_and_pause() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             let mut subgame1 = Subgame1::new();
             assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
-            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(()));
-            assert_eq!(subgame1.balance_of(accounts.alice, 1), 1000);
-            assert_eq!(subgame1.balance_of(accounts.bob, 1), 0);
-            assert_eq!(subgame1.safe_transfer_from(accounts.alice, accounts.bob, 1, 200), Ok(()));
-            assert_eq!(subgame1.balance_of(accounts.alice, 1), 800);
-            assert_eq!(subgame1.balance_of(accounts.bob, 1), 200);
+            assert_eq!(subgame1.mint(accounts.bob, 1, 1000), Ok(1000));
+            assert_eq!(subgame1.set_accept_contract_transfers(true), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame1.safe_transfer_from(accounts.bob, contract_id(), 1, 300), Ok(()));
+
+            set_sender(accounts.alice);
+            assert_eq!(subgame1.freeze(accounts.charlie), Ok(()));
+            assert_eq!(subgame1.recover(1, accounts.charlie, 300), Err(Error::AccountFrozen));
+            assert_eq!(subgame1.pause_id(1), Ok(()));
+            assert_eq!(subgame1.recover(1, accounts.bob, 300), Err(Error::TokenPaused));
+            assert_eq!(subgame1.balance_of(contract_id(), 1), 300);
+
+            assert_eq!(subgame1.unpause_id(1), Ok(()));
+            assert_eq!(subgame1.recover(1, accounts.bob, 300), Ok(()));
+            assert_eq!(subgame1.balance_of(accounts.bob, 1), 1000);
         }
 
         #[ink::test]
-        fn safe_batch_transfer_from_works() {
+        fn lock_state_reports_every_lock() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             let mut subgame1 = Subgame1::new();
             assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
             assert_eq!(subgame1.create([0x02].to_vec()), Ok(()));
-            assert_eq!(subgame1.mint_batch(accounts.alice, [1, 2].to_vec(), [1000, 1000].to_vec()), Ok(()));
-            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice].to_vec(), [1, 2].to_vec()), Ok([1000, 1000].to_vec()));
-            assert_eq!(subgame1.safe_batch_transfer_from(accounts.alice, accounts.bob, [1, 2].to_vec(), [200, 200].to_vec()), Ok(()));
-            assert_eq!(subgame1.balance_of_batch([accounts.alice, accounts.alice, accounts.bob, accounts.bob].to_vec(), [1, 2, 1, 2].to_vec()), Ok([800, 800, 200, 200].to_vec()));
+            assert_eq!(subgame1.mint(accounts.alice, 1, 100), Ok(100));
+            assert_eq!(subgame1.lock_state(), LockState {
+                paused_ids: Vec::new(),
+                frozen_accounts: 0,
+                approvals_paused: false,
+                minting_finalized: false,
+                transfers_locked_until_reveal: false,
+                locked_positions: 0,
+            });
+
+            assert_eq!(subgame1.pause_id(2), Ok(()));
+            assert_eq!(subgame1.freeze(accounts.eve), Ok(()));
+            assert_eq!(subgame1.set_approvals_paused(true), Ok(()));
+            assert_eq!(subgame1.set_reveal_block(10), Ok(()));
+            assert_eq!(subgame1.set_transfers_before_reveal(false), Ok(()));
+            assert_eq!(subgame1.lock(1, 40), Ok(()));
+            assert_eq!(subgame1.finalize_minting(), Ok(()));
+            assert_eq!(subgame1.lock_state(), LockState {
+                paused_ids: vec![2],
+                frozen_accounts: 1,
+                approvals_paused: true,
+                minting_finalized: true,
+                transfers_locked_until_reveal: true,
+                locked_positions: 1,
+            });
+
+            assert_eq!(subgame1.unpause_id(2), Ok(()));
+            assert_eq!(subgame1.unfreeze(accounts.eve), Ok(()));
+            assert_eq!(subgame1.set_approvals_paused(false), Ok(()));
+            assert_eq!(subgame1.set_transfers_before_reveal(true), Ok(()));
+            assert_eq!(subgame1.unlock(1, 40), Ok(()));
+            assert_eq!(subgame1.lock_state(), LockState {
+                paused_ids: Vec::new(),
+                frozen_accounts: 0,
+                approvals_paused: false,
+                minting_finalized: true,
+                transfers_locked_until_reveal: false,
+                locked_positions: 0,
+            });
         }
 
         #[ink::test]
-        fn set_approval_for_all_works() {
+        fn default_operators_are_capped() {
             let accounts =
                 ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
                     .expect("Cannot get accounts");
             let mut subgame1 = Subgame1::new();
-            assert_eq!(subgame1.create([0x01].to_vec()), Ok(()));
-            assert_eq!(subgame1.mint(accounts.alice, 1, 1000), Ok(()));
-            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), false);
-            assert_eq!(subgame1.set_approval_for_all(accounts.bob, true), Ok(()));
-            assert_eq!(subgame1.is_approved_for_all(accounts.alice, accounts.bob), true);
+            for i in 0..MAX_OPERATORS_PER_ACCOUNT {
+                assert_eq!(subgame1.set_default_operator(AccountId::from([i as u8 + 1; 32]), true), Ok(()));
+            }
+
+            let extra = AccountId::from([0xff; 32]);
+            assert_eq!(subgame1.set_default_operator(extra, true), Err(Error::TooManyOperators));
+            assert_eq!(subgame1.is_default_operator(extra), false);
+            assert_eq!(subgame1.set_default_operator(AccountId::from([1; 32]), true), Ok(()));
+
+            assert_eq!(subgame1.set_default_operator(AccountId::from([1; 32]), false), Ok(()));
+            assert_eq!(subgame1.set_default_operator(extra, true), Ok(()));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, extra), true);
+            assert_eq!(subgame1.revoke_all_operators(), Ok(()));
+            assert_eq!(subgame1.is_approved_for_all(accounts.alice, extra), false);
+            assert_eq!(subgame1.is_approved_for_all(accounts.bob, extra), true);
         }
     }
 }
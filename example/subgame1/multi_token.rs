@@ -0,0 +1,29 @@
+use ink_env::Environment;
+use ink_lang as ink;
+
+use common::{Error, TokenId, TokenBalance};
+
+type AccountId = <ink_env::DefaultEnvironment as Environment>::AccountId;
+
+/// The subset of the ERC1155 interface other contracts need to hold and move game items.
+///
+/// Game logic contracts call the token through the reference type generated for this trait
+/// instead of hardcoding message selectors.
+#[ink::trait_definition]
+pub trait MultiToken {
+    /// Returns the amount of `id` owned by `owner`.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId, id: TokenId) -> TokenBalance;
+
+    /// Transfers `value` of `id` from `from` to `to`. The caller must be `from` or its operator.
+    #[ink(message)]
+    fn safe_transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error>;
+
+    /// Creates `value` of `id` and assigns it to `to`. The caller must be allowed to mint `id`.
+    #[ink(message)]
+    fn mint(&mut self, to: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error>;
+
+    /// Destroys `value` of `id` held by `from`. The caller must be the creator of `id`.
+    #[ink(message)]
+    fn burn(&mut self, from: AccountId, id: TokenId, value: TokenBalance) -> Result<(), Error>;
+}
@@ -0,0 +1,65 @@
+use ink_env::Environment;
+use ink_lang as ink;
+use ink_prelude::{
+    format,
+    string::String,
+    vec::Vec,
+};
+
+use common::{Error, TokenId, TokenBalance};
+
+type AccountId = <ink_env::DefaultEnvironment as Environment>::AccountId;
+
+/// The PSP37 multi-token standard, as expected by Substrate-native tooling.
+///
+/// A `None` id refers to the collection as a whole: `balance_of` then returns the number of
+/// token types held by `owner`, `total_supply` the number of token types in circulation, and
+/// `approve`/`allowance` operate on the operator approval for all of the owner's tokens.
+#[ink::trait_definition]
+pub trait PSP37 {
+    /// Returns the amount of `id` owned by `owner`.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId, id: Option<TokenId>) -> TokenBalance;
+
+    /// Returns the total amount of `id` in circulation.
+    #[ink(message)]
+    fn total_supply(&self, id: Option<TokenId>) -> TokenBalance;
+
+    /// Returns the amount of `id` that `operator` is allowed to spend on behalf of `owner`.
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<TokenId>) -> TokenBalance;
+
+    /// Allows `operator` to spend `value` of the caller's `id`.
+    #[ink(message)]
+    fn approve(&mut self, operator: AccountId, id: Option<TokenId>, value: TokenBalance) -> Result<(), PSP37Error>;
+
+    /// Transfers `value` of `id` from the caller to `to`.
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, id: TokenId, value: TokenBalance, data: Vec<u8>) -> Result<(), PSP37Error>;
+
+    /// Transfers `value` of `id` from `from` to `to`, spending the caller's allowance.
+    #[ink(message)]
+    fn transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId, value: TokenBalance, data: Vec<u8>) -> Result<(), PSP37Error>;
+}
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP37Error {
+    Custom(String),
+    SelfApprove,
+    NotAllowed,
+    InsufficientBalance,
+    TransferToZeroAddress,
+    SafeTransferCheckFailed(String),
+}
+
+impl From<Error> for PSP37Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::InsufficientBalance { .. } => PSP37Error::InsufficientBalance,
+            Error::ApprovalForSelf => PSP37Error::SelfApprove,
+            Error::NotApproved | Error::OnlyCreator | Error::OnlyOwner => PSP37Error::NotAllowed,
+            error => PSP37Error::Custom(format!("{:?}", error)),
+        }
+    }
+}
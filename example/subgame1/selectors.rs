@@ -0,0 +1,9 @@
+/// Selectors of the public `Subgame1` messages, for off-chain clients that encode calls by hand.
+///
+/// ink! derives the selector of an inherent message from the first four bytes of the
+/// Blake2x256 hash of its name, so these only change if a message is renamed.
+pub const BALANCE_OF: [u8; 4] = [0x0f, 0x75, 0x5a, 0x56];
+pub const SAFE_TRANSFER_FROM: [u8; 4] = [0x8c, 0x47, 0x49, 0x72];
+pub const MINT: [u8; 4] = [0xcf, 0xdd, 0x9a, 0xa2];
+pub const BURN: [u8; 4] = [0xb1, 0xef, 0xc1, 0x7b];
+pub const SET_APPROVAL_FOR_ALL: [u8; 4] = [0xcf, 0xd0, 0xc2, 0x7b];
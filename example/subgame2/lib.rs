@@ -290,21 +290,89 @@ mod subgame2 {
     mod tests {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
+        use ink_env::{
+            call,
+            test,
+        };
+        use ink_lang as ink;
+
+        /// Answers `create_instance` with instance id 1.
+        struct MockedCreateInstance;
+
+        impl ink_env::test::ChainExtension for MockedCreateInstance {
+            fn func_id(&self) -> u32 {
+                1002
+            }
+
+            fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                scale::Encode::encode_to(&1u64, output);
+                0
+            }
+        }
+
+        /// Accepts every `create_token` call.
+        struct MockedCreateToken;
+
+        impl ink_env::test::ChainExtension for MockedCreateToken {
+            fn func_id(&self) -> u32 {
+                1003
+            }
+
+            fn call(&mut self, _input: &[u8], _output: &mut Vec<u8>) -> u32 {
+                0
+            }
+        }
+
+        fn register_extensions() {
+            ink_env::test::register_chain_extension(MockedCreateInstance);
+            ink_env::test::register_chain_extension(MockedCreateToken);
+        }
+
+        fn set_sender(sender: AccountId) {
+            let callee = ink_env::account_id::<ink_env::DefaultEnvironment>()
+                .unwrap_or([0x0; 32].into());
+            test::push_execution_context::<ink_env::DefaultEnvironment>(
+                sender,
+                callee,
+                1000000,
+                1000000,
+                test::CallData::new(call::Selector::new([0x00; 4])), // dummy
+            );
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            register_extensions();
+            let subgame2 = Subgame2::new([0x01].to_vec());
+            assert_eq!(subgame2.get_owner(), accounts.alice);
+            assert_eq!(subgame2.instance_id, 1);
+        }
 
-        /// We test if the default constructor does its job.
-        #[test]
-        fn default_works() {
-            let subgame2 = Subgame2::default();
-            assert_eq!(subgame2.get(), false);
+        #[ink::test]
+        fn create_token_works() {
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            register_extensions();
+            let mut subgame2 = Subgame2::new([0x01].to_vec());
+            assert_eq!(subgame2.create_token(1, false, [0x02].to_vec()), Ok(()));
+            assert_eq!(subgame2.uri_of(1), Some([0x02].to_vec()));
+
+            set_sender(accounts.bob);
+            assert_eq!(subgame2.create_token(2, false, [0x03].to_vec()), Err(Error::OnlyOwner));
+            assert_eq!(subgame2.set_uri(1, [0x04].to_vec()), Err(Error::OnlyOwner));
+            assert_eq!(subgame2.uri_of(2), None);
         }
 
-        /// We test a simple use case of our contract.
-        #[test]
-        fn it_works() {
-            let mut subgame2 = Subgame2::new(false);
-            assert_eq!(subgame2.get(), false);
-            subgame2.flip();
-            assert_eq!(subgame2.get(), true);
+        #[ink::test]
+        fn set_uri_works() {
+            register_extensions();
+            let mut subgame2 = Subgame2::new([0x01].to_vec());
+            assert_eq!(subgame2.set_uri(1, [0x02].to_vec()), Ok(()));
+            assert_eq!(subgame2.uri_of(1), Some([0x02].to_vec()));
         }
     }
 }